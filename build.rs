@@ -1,10 +1,70 @@
+use std::path::{Path, PathBuf};
+
+/// Library directory of Homebrew on Apple Silicon Macs
+const HOMEBREW_LIB_DIR: &str = "/opt/homebrew/lib";
+
 fn main() {
-    let _fluidsynth = pkg_config::probe_library("fluidsynth")
-        .expect("FluidSynth library not found. Please install FluidSynth development package.");
-    
-    // Add LAME library path and link
-    println!("cargo:rustc-link-search=native=/opt/homebrew/lib");
-    println!("cargo:rustc-link-lib=mp3lame");
+    link_fluidsynth();
+    link_lame();
+
+    if std::env::var_os("CARGO_FEATURE_OGG").is_some() {
+        let _vorbis = pkg_config::probe_library("vorbisenc")
+            .expect("libvorbis not found. Please install the libvorbis development package or disable the `ogg` feature.");
+    }
+
+    if std::env::var_os("CARGO_FEATURE_FLAC").is_some() {
+        let _flac = pkg_config::probe_library("flac")
+            .expect("libFLAC not found. Please install the FLAC development package or disable the `flac` feature.");
+    }
     
     println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// Reads a library directory override from the environment
+/// 
+/// A set directory is added to the link search path right away, so it takes
+/// precedence over whatever pkg-config reports afterwards.
+fn lib_dir_override(var: &str) -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed={}", var);
+
+    let lib_dir = PathBuf::from(std::env::var_os(var)?);
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    Some(lib_dir)
+}
+
+/// Links FluidSynth through pkg-config, or from `FLUIDSYNTH_LIB_DIR` when
+/// pkg-config has no entry for it
+fn link_fluidsynth() {
+    let lib_dir = lib_dir_override("FLUIDSYNTH_LIB_DIR");
+
+    if pkg_config::probe_library("fluidsynth").is_ok() {
+        return;
+    }
+    if lib_dir.is_none() {
+        panic!("FluidSynth library not found. Please install FluidSynth development package or set FLUIDSYNTH_LIB_DIR.");
+    }
+    println!("cargo:rustc-link-lib=fluidsynth");
+}
+
+/// Links LAME, which many distributions ship without a pkg-config file
+/// 
+/// Searches `LAME_LIB_DIR` first, then whatever pkg-config reports, then the
+/// Homebrew prefix on Apple Silicon; if none applies, the linker's default
+/// search path (e.g. `/usr/lib` on Linux, `/usr/local/lib` on Intel Macs)
+/// has to provide `libmp3lame`.
+fn link_lame() {
+    let lib_dir = lib_dir_override("LAME_LIB_DIR");
+    // The LAME functions are declared on the Rust side, so no headers are
+    // compiled; the variable is still tracked because build farms set it
+    // alongside `LAME_LIB_DIR` and expect a rebuild when it changes.
+    println!("cargo:rerun-if-env-changed=LAME_INCLUDE_DIR");
+
+    if pkg_config::probe_library("mp3lame").is_ok() {
+        return;
+    }
+
+    if lib_dir.is_none() && Path::new(HOMEBREW_LIB_DIR).is_dir() {
+        println!("cargo:rustc-link-search=native={}", HOMEBREW_LIB_DIR);
+    }
+    println!("cargo:rustc-link-lib=mp3lame");
 }
\ No newline at end of file
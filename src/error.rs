@@ -0,0 +1,112 @@
+/*!
+ * Error Types
+ *
+ * Defines the error type returned by the conversion APIs, so callers can
+ * match on specific failures instead of inspecting message strings.
+ */
+
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+/// Error returned by the conversion APIs
+/// 
+/// `Display` output matches the messages previously returned as plain strings.
+#[derive(Debug)]
+pub enum ConversionError {
+    /// FluidSynth settings, synthesizer, or player could not be created
+    FluidSynthInit(String),
+    /// SoundFont file could not be loaded
+    SoundFontLoad(String),
+    /// MIDI playback or synthesis failed
+    Synthesis(String),
+    /// MML is invalid or could not be converted to MIDI
+    InvalidMml(String),
+    /// MIDI data is malformed
+    InvalidMidi(String),
+    /// LAME encoder could not be initialized
+    LameInit(String),
+    /// LAME or another audio encoder failed while encoding
+    Encoding(String),
+    /// WAV input uses a format the encoder does not support
+    UnsupportedWavFormat(String),
+    /// Reading or writing WAV data failed
+    Wav {
+        /// What was being done when the error occurred
+        context: String,
+        /// Underlying hound error
+        source: hound::Error,
+    },
+    /// An argument was out of range or otherwise invalid
+    InvalidArgument(String),
+    /// Filesystem or stream I/O failed
+    Io(io::Error),
+    /// The conversion was cancelled by the caller
+    Cancelled,
+    /// The conversion ran longer than the allowed time
+    Timeout(Duration),
+    /// The synthesized audio is effectively silent
+    SilentOutput(String),
+}
+
+impl ConversionError {
+    /// Wraps an I/O error, prefixing its message with `context`
+    pub(crate) fn io(context: impl fmt::Display, error: io::Error) -> Self {
+        ConversionError::Io(io::Error::new(error.kind(), format!("{}: {}", context, error)))
+    }
+
+    /// Wraps a hound error, prefixing its message with `context`
+    pub(crate) fn wav(context: impl Into<String>, source: hound::Error) -> Self {
+        ConversionError::Wav { context: context.into(), source }
+    }
+
+    /// Creates an error for a string that cannot be passed to C because it
+    /// contains a NUL byte, e.g. `"MIDI path"`
+    /// 
+    /// The value is quoted with escapes, so the offending `\0` is visible.
+    pub(crate) fn nul_byte(what: &str, value: &str) -> Self {
+        ConversionError::InvalidArgument(format!("{} contains a NUL byte: {:?}", what, value))
+    }
+
+    /// Creates an I/O error for a missing file
+    pub(crate) fn not_found(message: impl Into<String>) -> Self {
+        ConversionError::Io(io::Error::new(io::ErrorKind::NotFound, message.into()))
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::FluidSynthInit(message)
+            | ConversionError::SoundFontLoad(message)
+            | ConversionError::Synthesis(message)
+            | ConversionError::InvalidMml(message)
+            | ConversionError::InvalidMidi(message)
+            | ConversionError::LameInit(message)
+            | ConversionError::Encoding(message)
+            | ConversionError::UnsupportedWavFormat(message)
+            | ConversionError::InvalidArgument(message)
+            | ConversionError::SilentOutput(message) => write!(f, "{}", message),
+            ConversionError::Wav { context, source } => write!(f, "{}: {}", context, source),
+            ConversionError::Io(error) => write!(f, "{}", error),
+            ConversionError::Cancelled => write!(f, "Conversion cancelled"),
+            ConversionError::Timeout(timeout) => write!(f, "Conversion timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConversionError::Wav { source, .. } => Some(source),
+            ConversionError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConversionError {
+    fn from(error: io::Error) -> Self {
+        ConversionError::Io(error)
+    }
+}
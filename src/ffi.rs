@@ -0,0 +1,182 @@
+/*!
+ * C API
+ *
+ * `extern "C"` functions for calling the conversion pipeline from C, or from
+ * Python through ctypes. Enabled by the `capi` feature; build a shared
+ * library with:
+ *
+ * ```text
+ * cargo rustc --lib --release --features capi --crate-type cdylib
+ * ```
+ *
+ * Callers only pass NUL-terminated UTF-8 strings and integers; everything
+ * allocated on the Rust side is freed before a function returns. Every
+ * function returns [`YKS_OK`] (0) on success or one of the negative
+ * `YKS_ERR_*` codes, and [`yks_last_error_message`] describes why the last
+ * call on the calling thread failed. Nothing is printed to stdout or stderr.
+ *
+ * ```text
+ * import ctypes
+ * yks = ctypes.CDLL("libyks_converter_example.so")
+ * yks.yks_last_error_message.restype = ctypes.c_char_p
+ * if yks.yks_convert_mml_to_mp3(b"song.mml", b"piano.sf2", b"song.mp3", 0) != 0:
+ *     print(yks.yks_last_error_message().decode())
+ * ```
+ */
+
+use crate::error::ConversionError;
+use crate::pipeline::ConversionPipeline;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The call succeeded
+pub const YKS_OK: c_int = 0;
+/// A pointer was null, a string was not UTF-8, or a value was out of range
+pub const YKS_ERR_INVALID_ARGUMENT: c_int = -1;
+/// FluidSynth could not be set up
+pub const YKS_ERR_FLUIDSYNTH_INIT: c_int = -2;
+/// The SoundFont could not be loaded
+pub const YKS_ERR_SOUNDFONT: c_int = -3;
+/// The MML or MIDI input is invalid
+pub const YKS_ERR_INVALID_INPUT: c_int = -4;
+/// Synthesis failed or produced silence
+pub const YKS_ERR_SYNTHESIS: c_int = -5;
+/// Reading WAV data or encoding the output failed
+pub const YKS_ERR_ENCODING: c_int = -6;
+/// A file could not be read or written
+pub const YKS_ERR_IO: c_int = -7;
+/// The conversion was cancelled or timed out
+pub const YKS_ERR_INTERRUPTED: c_int = -8;
+/// The converter panicked; this is a bug
+pub const YKS_ERR_PANIC: c_int = -99;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Returns the message of the last failed call on this thread
+/// 
+/// The string is owned by the library and stays valid until the next call
+/// into it from the same thread; copy it to keep it longer. Returns null if
+/// the last call on this thread succeeded.
+#[no_mangle]
+pub extern "C" fn yks_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Converts an MML file to MP3
+/// 
+/// # Arguments
+/// 
+/// * `mml` - Path to the input MML file
+/// * `sf2` - Path to the SoundFont file (.sf2)
+/// * `out` - Path for the output MP3 file
+/// * `instrument` - MIDI program number (0-127)
+/// 
+/// # Returns
+/// 
+/// Returns [`YKS_OK`] on success, or a negative `YKS_ERR_*` code.
+/// 
+/// # Safety
+/// 
+/// `mml`, `sf2` and `out` must be null or point to NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn yks_convert_mml_to_mp3(
+    mml: *const c_char,
+    sf2: *const c_char,
+    out: *const c_char,
+    instrument: c_int,
+) -> c_int {
+    run(|| {
+        let (mml, sf2, out) = (path_arg(mml, "mml")?, path_arg(sf2, "sf2")?, path_arg(out, "out")?);
+        let instrument = u8::try_from(instrument)
+            .ok()
+            .filter(|&program| program <= 127)
+            .ok_or_else(|| ConversionError::InvalidArgument(format!("Invalid instrument {} (expected 0-127)", instrument)))?;
+
+        let mut pipeline = ConversionPipeline::new()?;
+        // Progress must not be printed into the host program's stdout/stderr
+        pipeline.set_log_sink(|_, _| {});
+        pipeline.load_soundfont(sf2)?;
+        pipeline.set_instrument(instrument)?;
+        pipeline.convert_mml_to_mp3(mml, out)
+    })
+}
+
+/// Converts a MIDI file to MP3
+/// 
+/// # Arguments
+/// 
+/// * `midi` - Path to the input MIDI file (.mid, .midi)
+/// * `sf2` - Path to the SoundFont file (.sf2)
+/// * `out` - Path for the output MP3 file
+/// 
+/// # Returns
+/// 
+/// Returns [`YKS_OK`] on success, or a negative `YKS_ERR_*` code.
+/// 
+/// # Safety
+/// 
+/// `midi`, `sf2` and `out` must be null or point to NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn yks_convert_midi_to_mp3(midi: *const c_char, sf2: *const c_char, out: *const c_char) -> c_int {
+    run(|| {
+        let (midi, sf2, out) = (path_arg(midi, "midi")?, path_arg(sf2, "sf2")?, path_arg(out, "out")?);
+
+        let mut pipeline = ConversionPipeline::new()?;
+        // Progress must not be printed into the host program's stdout/stderr
+        pipeline.set_log_sink(|_, _| {});
+        pipeline.load_soundfont(sf2)?;
+        pipeline.convert_midi(midi, out)
+    })
+}
+
+/// Runs `convert`, recording its error and turning it into a status code
+/// 
+/// Panics are caught, since unwinding into C is undefined behavior.
+fn run(convert: impl FnOnce() -> Result<(), ConversionError>) -> c_int {
+    let (code, message) = match panic::catch_unwind(AssertUnwindSafe(convert)) {
+        Ok(Ok(())) => (YKS_OK, None),
+        Ok(Err(e)) => (error_code(&e), Some(e.to_string())),
+        Err(_) => (YKS_ERR_PANIC, Some("The converter panicked".to_string())),
+    };
+    LAST_ERROR.with(|last_error| {
+        // Messages never contain NUL bytes of their own, but paths quoted in them might
+        *last_error.borrow_mut() = message.map(|message| CString::new(message.replace('\0', "\\0")).unwrap_or_default());
+    });
+    code
+}
+
+/// Maps an error to its `YKS_ERR_*` code
+fn error_code(error: &ConversionError) -> c_int {
+    match error {
+        ConversionError::InvalidArgument(_) => YKS_ERR_INVALID_ARGUMENT,
+        ConversionError::FluidSynthInit(_) => YKS_ERR_FLUIDSYNTH_INIT,
+        ConversionError::SoundFontLoad(_) => YKS_ERR_SOUNDFONT,
+        ConversionError::InvalidMml(_) | ConversionError::InvalidMidi(_) => YKS_ERR_INVALID_INPUT,
+        ConversionError::Synthesis(_) | ConversionError::SilentOutput(_) => YKS_ERR_SYNTHESIS,
+        ConversionError::LameInit(_)
+        | ConversionError::Encoding(_)
+        | ConversionError::UnsupportedWavFormat(_)
+        | ConversionError::Wav { .. } => YKS_ERR_ENCODING,
+        ConversionError::Io(_) => YKS_ERR_IO,
+        ConversionError::Cancelled | ConversionError::Timeout(_) => YKS_ERR_INTERRUPTED,
+    }
+}
+
+/// Borrows a string argument, rejecting null pointers and invalid UTF-8
+/// 
+/// # Safety
+/// 
+/// `ptr` must be null or point to a NUL-terminated string that outlives the returned slice.
+unsafe fn path_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, ConversionError> {
+    if ptr.is_null() {
+        return Err(ConversionError::InvalidArgument(format!("Argument `{}` is null", name)));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| ConversionError::InvalidArgument(format!("Argument `{}` is not valid UTF-8", name)))
+}
\ No newline at end of file
@@ -0,0 +1,254 @@
+/*!
+ * FLAC Encoder Bindings
+ *
+ * Safe Rust bindings for the libFLAC stream encoder, used to write lossless
+ * FLAC files. Only available with the `flac` feature.
+ */
+
+use crate::error::ConversionError;
+use libc::{c_char, c_int, c_uint, c_void};
+use std::ffi::CString;
+
+/// libFLAC stream encoder (opaque)
+#[repr(C)]
+pub struct FLAC__StreamEncoder {
+    _private: [u8; 0],
+}
+
+/// libFLAC boolean (`int`, non-zero is true)
+pub type FlacBool = c_int;
+
+/// `FLAC__STREAM_ENCODER_INIT_STATUS_OK`
+pub const FLAC_INIT_STATUS_OK: c_int = 0;
+
+/// Highest compression level accepted by libFLAC
+pub const MAX_COMPRESSION_LEVEL: u8 = 8;
+
+#[link(name = "FLAC")]
+unsafe extern "C" {
+    pub fn FLAC__stream_encoder_new() -> *mut FLAC__StreamEncoder;
+    pub fn FLAC__stream_encoder_delete(encoder: *mut FLAC__StreamEncoder);
+    pub fn FLAC__stream_encoder_set_verify(encoder: *mut FLAC__StreamEncoder, value: FlacBool) -> FlacBool;
+    pub fn FLAC__stream_encoder_set_channels(encoder: *mut FLAC__StreamEncoder, value: c_uint) -> FlacBool;
+    pub fn FLAC__stream_encoder_set_bits_per_sample(encoder: *mut FLAC__StreamEncoder, value: c_uint) -> FlacBool;
+    pub fn FLAC__stream_encoder_set_sample_rate(encoder: *mut FLAC__StreamEncoder, value: c_uint) -> FlacBool;
+    pub fn FLAC__stream_encoder_set_compression_level(encoder: *mut FLAC__StreamEncoder, value: c_uint) -> FlacBool;
+    pub fn FLAC__stream_encoder_set_total_samples_estimate(encoder: *mut FLAC__StreamEncoder, value: u64) -> FlacBool;
+    pub fn FLAC__stream_encoder_init_file(
+        encoder: *mut FLAC__StreamEncoder,
+        filename: *const c_char,
+        progress_callback: *const c_void,
+        client_data: *mut c_void,
+    ) -> c_int;
+    pub fn FLAC__stream_encoder_process_interleaved(
+        encoder: *mut FLAC__StreamEncoder,
+        buffer: *const i32,
+        samples: c_uint,
+    ) -> FlacBool;
+    pub fn FLAC__stream_encoder_finish(encoder: *mut FLAC__StreamEncoder) -> FlacBool;
+    pub fn FLAC__stream_encoder_get_resolved_state_string(encoder: *const FLAC__StreamEncoder) -> *const c_char;
+}
+
+/// libFLAC stream decoder (opaque); only used by tests to check round trips
+#[cfg(test)]
+#[repr(C)]
+struct FLAC__StreamDecoder {
+    _private: [u8; 0],
+}
+
+/// Leading fields of `FLAC__FrameHeader`, which starts every `FLAC__Frame`
+#[cfg(test)]
+#[repr(C)]
+struct FlacFrameHeaderStart {
+    blocksize: u32,
+    sample_rate: u32,
+    channels: u32,
+}
+
+#[cfg(test)]
+type FlacWriteCallback = unsafe extern "C" fn(*const FLAC__StreamDecoder, *const FlacFrameHeaderStart, *const *const i32, *mut c_void) -> c_int;
+#[cfg(test)]
+type FlacErrorCallback = unsafe extern "C" fn(*const FLAC__StreamDecoder, c_int, *mut c_void);
+
+#[cfg(test)]
+#[link(name = "FLAC")]
+unsafe extern "C" {
+    fn FLAC__stream_decoder_new() -> *mut FLAC__StreamDecoder;
+    fn FLAC__stream_decoder_delete(decoder: *mut FLAC__StreamDecoder);
+    fn FLAC__stream_decoder_init_file(
+        decoder: *mut FLAC__StreamDecoder,
+        filename: *const c_char,
+        write_callback: FlacWriteCallback,
+        metadata_callback: *const c_void,
+        error_callback: FlacErrorCallback,
+        client_data: *mut c_void,
+    ) -> c_int;
+    fn FLAC__stream_decoder_process_until_end_of_stream(decoder: *mut FLAC__StreamDecoder) -> FlacBool;
+    fn FLAC__stream_decoder_finish(decoder: *mut FLAC__StreamDecoder) -> FlacBool;
+}
+
+/// Decodes a FLAC file into interleaved samples, each right-aligned in an `i32`
+/// 
+/// Returns `None` if libFLAC reports any error while decoding.
+#[cfg(test)]
+pub(crate) fn decode_file(path: &str) -> Option<Vec<i32>> {
+    /// Samples decoded so far, and whether libFLAC reported an error
+    type Decoded = (Vec<i32>, bool);
+
+    unsafe extern "C" fn write(
+        _decoder: *const FLAC__StreamDecoder,
+        frame: *const FlacFrameHeaderStart,
+        buffer: *const *const i32,
+        client_data: *mut c_void,
+    ) -> c_int {
+        let decoded = &mut *(client_data as *mut Decoded);
+        let header = &*frame;
+        let channels: Vec<&[i32]> = (0..header.channels as usize)
+            .map(|channel| std::slice::from_raw_parts(*buffer.add(channel), header.blocksize as usize))
+            .collect();
+        for index in 0..header.blocksize as usize {
+            decoded.0.extend(channels.iter().map(|samples| samples[index]));
+        }
+        0
+    }
+
+    unsafe extern "C" fn error(_decoder: *const FLAC__StreamDecoder, _status: c_int, client_data: *mut c_void) {
+        (*(client_data as *mut Decoded)).1 = true;
+    }
+
+    let path_cstr = CString::new(path).ok()?;
+    let mut decoded: Decoded = (Vec::new(), false);
+    unsafe {
+        let decoder = FLAC__stream_decoder_new();
+        if decoder.is_null() {
+            return None;
+        }
+        let client_data = &mut decoded as *mut Decoded as *mut c_void;
+        let ok = FLAC__stream_decoder_init_file(decoder, path_cstr.as_ptr(), write, std::ptr::null(), error, client_data) == FLAC_INIT_STATUS_OK
+            && FLAC__stream_decoder_process_until_end_of_stream(decoder) != 0;
+        FLAC__stream_decoder_finish(decoder);
+        FLAC__stream_decoder_delete(decoder);
+        (ok && !decoded.1).then_some(decoded.0)
+    }
+}
+
+/// Lossless FLAC encoder writing to a file
+/// 
+/// Verification is always enabled: libFLAC decodes every frame it writes and
+/// fails if the result differs from the input, so a successful encode is
+/// guaranteed to round-trip bit-exactly.
+pub struct FlacStreamEncoder {
+    encoder: *mut FLAC__StreamEncoder,
+    channels: usize,
+}
+
+impl FlacStreamEncoder {
+    /// Creates a FLAC encoder writing to `path`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Output FLAC file path
+    /// * `sample_rate` - Audio sample rate (e.g., 44100 for CD quality)
+    /// * `channels` - Number of audio channels (1-8)
+    /// * `bits_per_sample` - Sample width of the input (e.g., 16 or 24)
+    /// * `compression_level` - 0 (fastest) to 8 (smallest)
+    /// * `total_frames` - Number of frames per channel, written to the stream header
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(FlacStreamEncoder)` on success, or `Err(ConversionError)` with error message.
+    pub fn new(
+        path: &str,
+        sample_rate: u32,
+        channels: u16,
+        bits_per_sample: u16,
+        compression_level: u8,
+        total_frames: u64,
+    ) -> Result<Self, ConversionError> {
+        if compression_level > MAX_COMPRESSION_LEVEL {
+            return Err(ConversionError::InvalidArgument(format!(
+                "FLAC compression level must be between 0 and {}, got {}",
+                MAX_COMPRESSION_LEVEL, compression_level
+            )));
+        }
+        let path_cstr = CString::new(path)
+            .map_err(|_| ConversionError::nul_byte("FLAC output path", path))?;
+
+        unsafe {
+            let encoder = FLAC__stream_encoder_new();
+            if encoder.is_null() {
+                return Err(ConversionError::Encoding("Failed to create FLAC encoder".to_string()));
+            }
+            let flac = FlacStreamEncoder { encoder, channels: channels as usize };
+
+            let configured = FLAC__stream_encoder_set_verify(encoder, 1) != 0
+                && FLAC__stream_encoder_set_channels(encoder, channels as c_uint) != 0
+                && FLAC__stream_encoder_set_bits_per_sample(encoder, bits_per_sample as c_uint) != 0
+                && FLAC__stream_encoder_set_sample_rate(encoder, sample_rate as c_uint) != 0
+                && FLAC__stream_encoder_set_compression_level(encoder, compression_level as c_uint) != 0
+                && FLAC__stream_encoder_set_total_samples_estimate(encoder, total_frames) != 0;
+            if !configured {
+                return Err(ConversionError::Encoding("Failed to configure FLAC encoder".to_string()));
+            }
+
+            let status = FLAC__stream_encoder_init_file(encoder, path_cstr.as_ptr(), std::ptr::null(), std::ptr::null_mut());
+            if status != FLAC_INIT_STATUS_OK {
+                return Err(ConversionError::Encoding(format!(
+                    "Failed to initialize FLAC encoder for {} Hz, {} channels, {}-bit audio: {}",
+                    sample_rate, channels, bits_per_sample, flac.state()
+                )));
+            }
+
+            Ok(flac)
+        }
+    }
+
+    /// Encodes interleaved samples, each right-aligned in an `i32`
+    pub fn encode(&mut self, samples: &[i32]) -> Result<(), ConversionError> {
+        if !samples.len().is_multiple_of(self.channels) {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Sample count {} is not a multiple of the channel count {}",
+                samples.len(),
+                self.channels
+            )));
+        }
+
+        let frames = samples.len() / self.channels;
+        let ok = unsafe { FLAC__stream_encoder_process_interleaved(self.encoder, samples.as_ptr(), frames as c_uint) };
+        if ok == 0 {
+            return Err(ConversionError::Encoding(format!("FLAC encoding error: {}", self.state())));
+        }
+        Ok(())
+    }
+
+    /// Flushes the remaining frames and finalizes the stream header
+    pub fn finish(self) -> Result<(), ConversionError> {
+        let ok = unsafe { FLAC__stream_encoder_finish(self.encoder) };
+        if ok == 0 {
+            return Err(ConversionError::Encoding(format!("FLAC finish error: {}", self.state())));
+        }
+        Ok(())
+    }
+
+    /// Returns libFLAC's description of the encoder state
+    fn state(&self) -> String {
+        unsafe {
+            let state = FLAC__stream_encoder_get_resolved_state_string(self.encoder);
+            if state.is_null() {
+                "unknown error".to_string()
+            } else {
+                std::ffi::CStr::from_ptr(state).to_string_lossy().into_owned()
+            }
+        }
+    }
+}
+
+impl Drop for FlacStreamEncoder {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.encoder.is_null() {
+                FLAC__stream_encoder_delete(self.encoder);
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,116 @@
+/*!
+ * FLAC Encoder Module
+ *
+ * Handles lossless conversion from WAV format to FLAC using libFLAC.
+ * Only available with the `flac` feature.
+ */
+
+use crate::error::ConversionError;
+use crate::flac_bindings::FlacStreamEncoder;
+use hound::{SampleFormat, WavReader};
+
+/// Default FLAC compression level, libFLAC's own default
+pub const DEFAULT_COMPRESSION_LEVEL: u8 = 5;
+
+/// Lossless FLAC encoder using libFLAC
+/// 
+/// The synthesized WAV is the lossless source, so samples are passed through
+/// at their original bit depth without dithering or requantization.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use yks_converter_example::flac_encoder::FlacEncoder;
+/// 
+/// FlacEncoder::convert_wav_to_flac("song.wav", "song.flac", 5)?;
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
+/// ```
+pub struct FlacEncoder;
+
+impl FlacEncoder {
+    /// Converts a WAV file to FLAC
+    /// 
+    /// libFLAC's verify mode is enabled, so the encode fails if any frame would
+    /// not decode back to exactly the input samples.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (integer PCM; FLAC cannot store float samples)
+    /// * `flac_path` - Path for the output FLAC file
+    /// * `compression_level` - 0 (fastest) to 8 (smallest); see [`DEFAULT_COMPRESSION_LEVEL`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_wav_to_flac(wav_path: &str, flac_path: &str, compression_level: u8) -> Result<(), ConversionError> {
+        let mut reader = WavReader::open(wav_path)
+            .map_err(|e| ConversionError::wav("Failed to open WAV file", e))?;
+
+        let spec = reader.spec();
+        if spec.sample_format != SampleFormat::Int {
+            return Err(ConversionError::UnsupportedWavFormat(format!(
+                "Unsupported WAV sample format for FLAC: {}-bit {:?} (FLAC stores integer samples only)",
+                spec.bits_per_sample, spec.sample_format
+            )));
+        }
+
+        let samples: Vec<i32> = reader.samples::<i32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| ConversionError::wav("Failed to read samples", e))?;
+
+        let mut encoder = FlacStreamEncoder::new(
+            flac_path,
+            spec.sample_rate,
+            spec.channels,
+            spec.bits_per_sample,
+            compression_level,
+            reader.duration() as u64,
+        )?;
+        encoder.encode(&samples)?;
+        encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flac_bindings;
+    use hound::{WavSpec, WavWriter};
+
+    #[test]
+    fn flac_decodes_to_the_source_wav_samples() {
+        for (channels, bits_per_sample) in [(2, 16), (1, 24)] {
+            let spec = WavSpec { channels, sample_rate: 44100, bits_per_sample, sample_format: SampleFormat::Int };
+            let base = std::env::temp_dir().join(format!("yks_flac_round_trip_{}_{}_{}", std::process::id(), channels, bits_per_sample));
+            let wav_path = base.with_extension("wav").to_str().unwrap().to_string();
+            let flac_path = base.with_extension("flac").to_str().unwrap().to_string();
+
+            // A sine with a pseudo-random component, so the encoder cannot
+            // predict every sample
+            let amplitude = ((1i64 << (bits_per_sample - 1)) - 1) as f64 / 2.0;
+            let mut state = 0x1234_5678u32;
+            let samples: Vec<i32> = (0..44100 * channels as usize)
+                .map(|i| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    let sine = (i as f64 * 440.0 * std::f64::consts::TAU / 44100.0).sin() * amplitude;
+                    (sine + (state % 1024) as f64 - 512.0) as i32
+                })
+                .collect();
+            let mut writer = WavWriter::create(&wav_path, spec).unwrap();
+            for &sample in &samples {
+                writer.write_sample(sample).unwrap();
+            }
+            writer.finalize().unwrap();
+
+            let encoded = FlacEncoder::convert_wav_to_flac(&wav_path, &flac_path, DEFAULT_COMPRESSION_LEVEL);
+            let decoded = flac_bindings::decode_file(&flac_path);
+            let _ = std::fs::remove_file(&wav_path);
+            let _ = std::fs::remove_file(&flac_path);
+
+            encoded.unwrap();
+            assert!(decoded.as_ref() == Some(&samples), "{}-bit, {} channel FLAC does not match its source", bits_per_sample, channels);
+        }
+    }
+}
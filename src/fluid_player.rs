@@ -0,0 +1,145 @@
+/*!
+ * FluidSynth MIDI Player
+ *
+ * Safe wrapper around a FluidSynth MIDI player. The player is deleted when
+ * the wrapper is dropped, so early returns and `?` cannot leak it.
+ */
+
+use crate::*;
+use crate::error::ConversionError;
+use std::ffi::CString;
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of players dropped so far, so tests can check none is leaked
+#[cfg(test)]
+pub(crate) static DROPPED_PLAYERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Owned FluidSynth MIDI player
+/// 
+/// A player drives the synthesizer it was created for, so it must be dropped
+/// before that synth is deleted; [`crate::midi_converter::MidiConverter`]
+/// only keeps players for the duration of a `&mut self` borrow for that reason.
+pub struct FluidPlayer {
+    player: *mut fluid_player_t,
+}
+
+impl FluidPlayer {
+    /// Creates a player for `synth`
+    /// 
+    /// # Safety
+    /// 
+    /// `synth` must be a valid synthesizer that outlives the returned player.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(FluidPlayer)` on success, or `Err(ConversionError)` with error message.
+    pub(crate) unsafe fn new(synth: *mut fluid_synth_t) -> Result<Self, ConversionError> {
+        let player = new_fluid_player(synth);
+        if player.is_null() {
+            return Err(ConversionError::FluidSynthInit("Failed to create MIDI player".to_string()));
+        }
+        Ok(FluidPlayer { player })
+    }
+
+    /// Queues a MIDI file for playback
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the MIDI file (.mid, .midi)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn add(&mut self, midi_path: &str) -> Result<(), ConversionError> {
+        let midi_cstring = CString::new(midi_path)
+            .map_err(|_| ConversionError::nul_byte("MIDI path", midi_path))?;
+        if unsafe { fluid_player_add(self.player, midi_cstring.as_ptr()) } != 0 {
+            return Err(ConversionError::Synthesis("Failed to add MIDI file to player".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Queues MIDI data held in memory for playback
+    /// 
+    /// FluidSynth copies the data, so it only has to live until this returns.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_data` - Contents of a Standard MIDI File
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn add_mem(&mut self, midi_data: &[u8]) -> Result<(), ConversionError> {
+        let result = unsafe {
+            fluid_player_add_mem(self.player, midi_data.as_ptr() as *const c_void, midi_data.len())
+        };
+        if result != 0 {
+            return Err(ConversionError::Synthesis("Failed to add MIDI file to player".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Scales the tempo of the queued MIDI data, see
+    /// [`crate::midi_converter::MidiConverter::set_tempo_scale`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_tempo_scale(&mut self, scale: f64) -> Result<(), ConversionError> {
+        if unsafe { fluid_player_set_tempo(self.player, FLUID_PLAYER_TEMPO_INTERNAL, scale) } != 0 {
+            return Err(ConversionError::Synthesis("Failed to set MIDI player tempo".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Starts playback
+    /// 
+    /// The player only sends events to the synth; audio is produced by
+    /// rendering from the synth while [`FluidPlayer::is_playing`] holds.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn play(&mut self) -> Result<(), ConversionError> {
+        if unsafe { fluid_player_play(self.player) } != 0 {
+            return Err(ConversionError::Synthesis("Failed to start MIDI player".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns FluidSynth's player status (e.g. [`FLUID_PLAYER_PLAYING`])
+    pub fn status(&self) -> i32 {
+        unsafe { fluid_player_get_status(self.player) }
+    }
+
+    /// Returns whether the player still has events to play
+    pub fn is_playing(&self) -> bool {
+        self.status() == FLUID_PLAYER_PLAYING as i32
+    }
+
+    /// Returns the fraction of ticks played so far, from 0.0 to 1.0
+    /// 
+    /// Returns 0.0 while the length of the MIDI data is unknown.
+    pub fn progress(&self) -> f64 {
+        let (current_ticks, total_ticks) = unsafe {
+            (fluid_player_get_current_tick(self.player), fluid_player_get_total_ticks(self.player))
+        };
+        if total_ticks > 0 {
+            (current_ticks as f64 / total_ticks as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Drop for FluidPlayer {
+    fn drop(&mut self) {
+        unsafe {
+            delete_fluid_player(self.player);
+        }
+        #[cfg(test)]
+        DROPPED_PLAYERS.fetch_add(1, Ordering::SeqCst);
+    }
+}
\ No newline at end of file
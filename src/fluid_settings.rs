@@ -0,0 +1,136 @@
+/*!
+ * FluidSynth Settings
+ *
+ * Safe wrapper around a FluidSynth settings object. Setting names are
+ * converted to C strings once per call and every value FluidSynth refuses is
+ * reported as an error naming the setting.
+ */
+
+use crate::*;
+use crate::error::ConversionError;
+use std::ffi::CString;
+use std::fmt;
+
+/// Owned FluidSynth settings object
+/// 
+/// A synthesizer created from these settings keeps a pointer to them, so the
+/// settings must outlive it; [`crate::midi_converter::MidiConverter`] deletes
+/// its synth before its settings for that reason.
+pub struct FluidSettings {
+    settings: *mut fluid_settings_t,
+}
+
+impl FluidSettings {
+    /// Creates a settings object with FluidSynth's defaults
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(FluidSettings)` on success, or `Err(ConversionError)` with error message.
+    pub fn new() -> Result<Self, ConversionError> {
+        let settings = unsafe { new_fluid_settings() };
+        if settings.is_null() {
+            return Err(ConversionError::FluidSynthInit("Failed to create FluidSynth settings".to_string()));
+        }
+        Ok(FluidSettings { settings })
+    }
+
+    /// Sets a string setting, e.g. `synth.midi-bank-select`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Setting name
+    /// * `value` - New value
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if FluidSynth rejects the setting.
+    /// A name or value containing a NUL byte is rejected with `InvalidArgument`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::error::ConversionError;
+    /// use yks_converter_example::fluid_settings::FluidSettings;
+    /// 
+    /// let mut settings = FluidSettings::new()?;
+    /// let bad_name = settings.set_str("synth.midi-bank\0select", "gs");
+    /// let bad_value = settings.set_str("synth.midi-bank-select", "g\0s");
+    /// assert!(matches!(bad_name, Err(ConversionError::InvalidArgument(_))));
+    /// assert!(matches!(bad_value, Err(ConversionError::InvalidArgument(_))));
+    /// assert!(matches!(settings.set_num("synth.ga\0in", 1.0), Err(ConversionError::InvalidArgument(_))));
+    /// # Ok::<(), ConversionError>(())
+    /// ```
+    pub fn set_str(&mut self, name: &str, value: &str) -> Result<(), ConversionError> {
+        let name_cstring = setting_name(name)?;
+        let value_cstring = CString::new(value)
+            .map_err(|_| ConversionError::nul_byte(&format!("Value for setting {}", name), value))?;
+        let result = unsafe { fluid_settings_setstr(self.settings, name_cstring.as_ptr(), value_cstring.as_ptr()) };
+        check_result(result, name, value)
+    }
+
+    /// Sets a floating point setting, e.g. `synth.gain`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Setting name
+    /// * `value` - New value
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if FluidSynth rejects the setting.
+    pub fn set_num(&mut self, name: &str, value: f64) -> Result<(), ConversionError> {
+        let name_cstring = setting_name(name)?;
+        let result = unsafe { fluid_settings_setnum(self.settings, name_cstring.as_ptr(), value) };
+        check_result(result, name, value)
+    }
+
+    /// Sets an integer setting, e.g. `synth.polyphony`
+    /// 
+    /// Boolean settings such as `synth.reverb.active` are integers (0 or 1).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Setting name
+    /// * `value` - New value
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if FluidSynth rejects the setting.
+    pub fn set_int(&mut self, name: &str, value: i32) -> Result<(), ConversionError> {
+        let name_cstring = setting_name(name)?;
+        let result = unsafe { fluid_settings_setint(self.settings, name_cstring.as_ptr(), value) };
+        check_result(result, name, value)
+    }
+
+    /// Returns the raw pointer for passing to FluidSynth
+    pub(crate) fn as_ptr(&self) -> *mut fluid_settings_t {
+        self.settings
+    }
+}
+
+impl Drop for FluidSettings {
+    fn drop(&mut self) {
+        unsafe {
+            delete_fluid_settings(self.settings);
+        }
+    }
+}
+
+/// Converts a setting name for FluidSynth
+/// 
+/// Callers bind the returned `CString` to a local before taking `.as_ptr()`.
+/// The pointer is only valid while the `CString` lives, and a temporary is
+/// dropped at the end of its statement: `let ptr = CString::new(name)?.as_ptr();`
+/// leaves `ptr` dangling, and inlining the call into the FFI call makes the code
+/// correct only as long as nobody splits it across statements.
+fn setting_name(name: &str) -> Result<CString, ConversionError> {
+    CString::new(name).map_err(|_| ConversionError::nul_byte("Setting name", name))
+}
+
+/// Turns a FluidSynth status code into an error naming the rejected setting
+fn check_result(result: c_int, name: &str, value: impl fmt::Display) -> Result<(), ConversionError> {
+    if result != 0 {
+        return Err(ConversionError::InvalidArgument(format!("FluidSynth rejected setting {} = {}", name, value)));
+    }
+    Ok(())
+}
\ No newline at end of file
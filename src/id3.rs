@@ -0,0 +1,237 @@
+/*!
+ * ID3v2 Tag Module
+ *
+ * Minimal ID3v2.3 tag writing and reading for generated MP3 files.
+ * Tags are prepended to the MP3 stream before the first audio frame.
+ */
+
+/// TXXX description used for the frame that stores the source MML
+pub const MML_SOURCE_DESCRIPTION: &str = "MML";
+
+/// Maximum number of bytes of MML text embedded into a tag
+/// 
+/// Longer MML is truncated at the last character boundary below this limit,
+/// so the embedded copy of very large songs is incomplete.
+pub const MAX_EMBEDDED_MML_BYTES: usize = 64 * 1024;
+
+/// ID3v2.3 tag builder
+/// 
+/// # Example
+/// 
+/// ```
+/// use yks_converter_example::id3::Id3Tag;
+/// 
+/// let mut tag = Id3Tag::new();
+/// tag.add_user_text("MML", "MML@t120cde;");
+/// let bytes = tag.to_bytes();
+/// assert_eq!(Id3Tag::read_user_text(&bytes, "MML").as_deref(), Some("MML@t120cde;"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Id3Tag {
+    frames: Vec<([u8; 4], Vec<u8>)>,
+}
+
+impl Id3Tag {
+    /// Creates an empty tag
+    pub fn new() -> Self {
+        Id3Tag { frames: Vec::new() }
+    }
+
+    /// Returns `true` if no frames have been added
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Adds a user-defined text (TXXX) frame
+    /// 
+    /// # Arguments
+    /// 
+    /// * `description` - Frame description used to look the value up again
+    /// * `value` - Text value to store
+    pub fn add_user_text(&mut self, description: &str, value: &str) {
+        let encoding = text_encoding(description).max(text_encoding(value));
+        let mut body = vec![encoding];
+        body.extend(encode_text(description, encoding));
+        body.extend(terminator(encoding));
+        body.extend(encode_text(value, encoding));
+        self.frames.push((*b"TXXX", body));
+    }
+
+    /// Adds a standard text information frame such as `TIT2` (title) or `TPE1` (artist)
+    /// 
+    /// # Arguments
+    /// 
+    /// * `id` - Four-character frame ID starting with `T`
+    /// * `value` - Text value to store
+    pub fn add_text(&mut self, id: [u8; 4], value: &str) {
+        let encoding = text_encoding(value);
+        let mut body = vec![encoding];
+        body.extend(encode_text(value, encoding));
+        self.frames.push((id, body));
+    }
+
+    /// Adds the source MML as a TXXX frame, truncated to [`MAX_EMBEDDED_MML_BYTES`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code the MP3 was rendered from
+    pub fn add_mml_source(&mut self, mml_text: &str) {
+        let mut end = mml_text.len().min(MAX_EMBEDDED_MML_BYTES);
+        while !mml_text.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.add_user_text(MML_SOURCE_DESCRIPTION, &mml_text[..end]);
+    }
+
+    /// Serializes the tag into ID3v2.3 bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut frames = Vec::new();
+        for (id, body) in &self.frames {
+            frames.extend_from_slice(id);
+            frames.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            frames.extend_from_slice(&[0, 0]);
+            frames.extend_from_slice(body);
+        }
+
+        let size = frames.len() as u32;
+        let mut bytes = b"ID3\x03\x00\x00".to_vec();
+        bytes.extend_from_slice(&[
+            ((size >> 21) & 0x7f) as u8,
+            ((size >> 14) & 0x7f) as u8,
+            ((size >> 7) & 0x7f) as u8,
+            (size & 0x7f) as u8,
+        ]);
+        bytes.extend(frames);
+        bytes
+    }
+
+    /// Reads a user-defined text (TXXX) frame from the start of an MP3 stream
+    /// 
+    /// # Arguments
+    /// 
+    /// * `data` - Bytes from the beginning of an MP3 file
+    /// * `description` - Description of the TXXX frame to find
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the frame value, or `None` if there is no tag or no matching frame.
+    pub fn read_user_text(data: &[u8], description: &str) -> Option<String> {
+        frames(data)?
+            .into_iter()
+            .filter(|(id, _)| id == b"TXXX")
+            .find_map(|(_, body)| {
+                let (&encoding, rest) = body.split_first()?;
+                let split = find_terminator(rest, encoding)?;
+                let frame_description = decode_text(&rest[..split], encoding)?;
+                let value = &rest[split + terminator(encoding).len()..];
+                (frame_description == description).then(|| decode_text(value, encoding))?
+            })
+    }
+
+    /// Reads a standard text information frame from the start of an MP3 stream
+    /// 
+    /// # Arguments
+    /// 
+    /// * `data` - Bytes from the beginning of an MP3 file
+    /// * `id` - Four-character frame ID, e.g. `*b"TIT2"`
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the frame value, or `None` if there is no tag or no matching frame.
+    pub fn read_text(data: &[u8], id: [u8; 4]) -> Option<String> {
+        frames(data)?
+            .into_iter()
+            .find(|(frame_id, _)| *frame_id == id)
+            .and_then(|(_, body)| {
+                let (&encoding, rest) = body.split_first()?;
+                decode_text(rest, encoding)
+            })
+    }
+
+    /// Returns the size in bytes of the ID3v2 tag at the start of `data`, if any
+    pub fn tag_size(data: &[u8]) -> Option<usize> {
+        if data.len() < 10 || &data[..3] != b"ID3" {
+            return None;
+        }
+        Some(10 + syncsafe(&data[6..10]))
+    }
+}
+
+/// Splits the ID3v2 tag at the start of `data` into (frame id, body) pairs
+fn frames(data: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let end = Id3Tag::tag_size(data)?.min(data.len());
+    let major_version = data[3];
+    let mut frames = Vec::new();
+    let mut pos = 10;
+
+    while pos + 10 <= end && data[pos] != 0 {
+        let id = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        let size = if major_version >= 4 {
+            syncsafe(&data[pos + 4..pos + 8])
+        } else {
+            u32::from_be_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize
+        };
+        let body_start = pos + 10;
+        let body_end = body_start.checked_add(size)?.min(end);
+        frames.push((id, &data[body_start..body_end]));
+        pos = body_end;
+    }
+
+    Some(frames)
+}
+
+fn syncsafe(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |acc, &b| (acc << 7) | (b & 0x7f) as usize)
+}
+
+/// Picks ISO-8859-1 (0) when possible, otherwise UTF-16 with BOM (1)
+fn text_encoding(text: &str) -> u8 {
+    if text.chars().all(|c| (c as u32) <= 0xff) { 0 } else { 1 }
+}
+
+fn encode_text(text: &str, encoding: u8) -> Vec<u8> {
+    if encoding == 0 {
+        text.chars().map(|c| c as u8).collect()
+    } else {
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+}
+
+fn terminator(encoding: u8) -> &'static [u8] {
+    if encoding == 1 || encoding == 2 { &[0, 0] } else { &[0] }
+}
+
+fn find_terminator(data: &[u8], encoding: u8) -> Option<usize> {
+    if terminator(encoding).len() == 2 {
+        (0..data.len().saturating_sub(1)).step_by(2).find(|&i| data[i] == 0 && data[i + 1] == 0)
+    } else {
+        data.iter().position(|&b| b == 0)
+    }
+}
+
+fn decode_text(data: &[u8], encoding: u8) -> Option<String> {
+    let data = data.strip_suffix(terminator(encoding)).unwrap_or(data);
+    match encoding {
+        0 => Some(data.iter().map(|&b| b as char).collect()),
+        1 | 2 => {
+            let (big_endian, data) = match data {
+                [0xfe, 0xff, rest @ ..] => (true, rest),
+                [0xff, 0xfe, rest @ ..] => (false, rest),
+                _ => (encoding == 2, data),
+            };
+            let units: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|pair| if big_endian {
+                    u16::from_be_bytes([pair[0], pair[1]])
+                } else {
+                    u16::from_le_bytes([pair[0], pair[1]])
+                })
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+        3 => String::from_utf8(data.to_vec()).ok(),
+        _ => None,
+    }
+}
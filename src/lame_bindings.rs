@@ -5,7 +5,8 @@
  * Provides high-quality MP3 encoding with configurable settings.
  */
 
-use libc::{c_int, c_uchar};
+use crate::error::ConversionError;
+use libc::{c_char, c_int, c_uchar};
 
 /// LAME global flags structure (opaque)
 #[repr(C)]
@@ -16,6 +17,50 @@ pub struct lame_global_flags {
 /// Type alias for LAME global flags pointer
 pub type LameT = *mut lame_global_flags;
 
+/// Default LAME quality: 0 is the slowest, highest-quality algorithm and 9 the fastest
+pub const DEFAULT_QUALITY: u8 = 0;
+
+/// Returns the MP3 output buffer size LAME requires for one encode call
+/// 
+/// LAME documents the worst case as `1.25 * num_samples + 7200` bytes, where
+/// `num_samples` is the number of samples per channel passed to the encoder.
+/// A buffer of `lame_mp3_buffer_size(0)` is large enough for `lame_encode_flush`.
+pub fn lame_mp3_buffer_size(num_samples: usize) -> usize {
+    (num_samples * 5).div_ceil(4) + 7200
+}
+
+/// Sample rates an MP3 stream can have (MPEG-1, MPEG-2 and MPEG-2.5)
+pub const SUPPORTED_OUT_SAMPLE_RATES: [u32; 9] = [8000, 11025, 12000, 16000, 22050, 24000, 32000, 44100, 48000];
+
+/// Checks that `sample_rate` is a legal MP3 output sample rate
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if supported, or `Err(ConversionError)` listing the accepted values.
+pub fn validate_out_sample_rate(sample_rate: u32) -> Result<(), ConversionError> {
+    if SUPPORTED_OUT_SAMPLE_RATES.contains(&sample_rate) {
+        Ok(())
+    } else {
+        Err(ConversionError::InvalidArgument(format!(
+            "Unsupported MP3 output sample rate: {} Hz (supported: {:?})",
+            sample_rate, SUPPORTED_OUT_SAMPLE_RATES
+        )))
+    }
+}
+
+/// MP3 channel mode, mirroring LAME's `MPEG_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StereoMode {
+    /// Independent left and right channels sharing the bitrate
+    Stereo = 0,
+    /// Mid/side coding where it saves bits; LAME's usual choice for stereo
+    JointStereo = 1,
+    /// Two fully independent channels, each with half the bitrate
+    DualChannel = 2,
+    /// Single channel; stereo input is downmixed
+    Mono = 3,
+}
+
 #[link(name = "mp3lame")]
 unsafe extern "C" {
     pub fn lame_init() -> LameT;
@@ -28,6 +73,13 @@ unsafe extern "C" {
     pub fn lame_set_out_samplerate(gfp: LameT, sample_rate: c_int) -> c_int;
     pub fn lame_set_brate(gfp: LameT, brate: c_int) -> c_int;
     pub fn lame_set_quality(gfp: LameT, quality: c_int) -> c_int;
+    pub fn lame_set_mode(gfp: LameT, mode: c_int) -> c_int;
+    pub fn lame_set_error_protection(gfp: LameT, enable: c_int) -> c_int;
+    pub fn lame_set_disable_reservoir(gfp: LameT, disable: c_int) -> c_int;
+    
+    // Read back parameters, valid after lame_init_params
+    pub fn lame_get_out_samplerate(gfp: LameT) -> c_int;
+    pub fn lame_get_brate(gfp: LameT) -> c_int;
     
     // Encoding functions - use short (i16) instead of int
     pub fn lame_encode_buffer_interleaved(
@@ -52,14 +104,30 @@ unsafe extern "C" {
         mp3buf: *mut c_uchar,
         size: c_int,
     ) -> c_int;
+    
+    // Library information
+    pub fn get_lame_version() -> *const c_char;
 }
 
 /// High-quality MP3 encoder using LAME
 /// 
 /// Provides a safe wrapper around the LAME encoder with optimal settings
 /// for music production and audio conversion.
+/// 
+/// One encoder can produce several MP3 streams in a row: call
+/// [`LameEncoder::reset`] after [`LameEncoder::flush`] to start the next one.
+/// Every stream shares the sample rate and channel count the encoder was
+/// created with.
 pub struct LameEncoder {
     lame: LameT,
+    sample_rate: u32,
+    channels: u16,
+    out_sample_rate: Option<u32>,
+    bitrate: u32,
+    mode: Option<StereoMode>,
+    quality: u8,
+    error_protection: bool,
+    disable_reservoir: bool,
 }
 
 impl LameEncoder {
@@ -73,27 +141,251 @@ impl LameEncoder {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(LameEncoder)` on success, or `Err(String)` with error message.
-    pub fn new(sample_rate: u32, channels: u16, bitrate: u32) -> Result<Self, String> {
+    /// Returns `Ok(LameEncoder)` on success, or `Err(ConversionError)` with error message.
+    pub fn new(sample_rate: u32, channels: u16, bitrate: u32) -> Result<Self, ConversionError> {
+        Self::create(sample_rate, channels, bitrate, None, DEFAULT_QUALITY, None)
+    }
+
+    /// Creates a new LAME encoder that resamples to another output sample rate
+    /// 
+    /// LAME resamples internally while encoding, e.g. 44100 Hz synthesis down
+    /// to 22050 Hz for bandwidth-limited delivery. Rates below 32000 Hz are
+    /// MPEG-2/2.5 streams, which top out at 160 kbps; higher bitrates are
+    /// rejected with `LameInit`, see [`crate::mp3_encoder::supported_bitrates_at`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample_rate` - Input sample rate (e.g., 44100)
+    /// * `channels` - Number of audio channels (1 for mono, 2 for stereo)
+    /// * `bitrate` - MP3 bitrate in kbps (e.g., 96)
+    /// * `out_sample_rate` - Output rate, one of [`SUPPORTED_OUT_SAMPLE_RATES`],
+    ///   or `None` to keep the input rate
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(LameEncoder)` on success, or `Err(ConversionError)` with error message.
+    pub fn new_with_resample(sample_rate: u32, channels: u16, bitrate: u32, out_sample_rate: Option<u32>) -> Result<Self, ConversionError> {
+        Self::create(sample_rate, channels, bitrate, None, DEFAULT_QUALITY, out_sample_rate)
+    }
+
+    /// Creates a new LAME encoder using the given algorithm quality
+    /// 
+    /// Lower values are slower and sound better; 2 is LAME's recommended
+    /// "near-best" setting and is several times faster than 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample_rate` - Audio sample rate (e.g., 44100 for CD quality)
+    /// * `channels` - Number of audio channels (1 for mono, 2 for stereo)
+    /// * `bitrate` - MP3 bitrate in kbps (e.g., 192 for high quality)
+    /// * `quality` - Algorithm quality (0-9, 0 is best)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(LameEncoder)` on success, or `Err(ConversionError)` with error message.
+    pub fn new_with_quality(sample_rate: u32, channels: u16, bitrate: u32, quality: u8) -> Result<Self, ConversionError> {
+        Self::create(sample_rate, channels, bitrate, None, quality, None)
+    }
+
+    /// Creates a new LAME encoder using an explicit channel mode
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample_rate` - Audio sample rate (e.g., 44100 for CD quality)
+    /// * `channels` - Number of audio channels (1 for mono, 2 for stereo)
+    /// * `bitrate` - MP3 bitrate in kbps (e.g., 192 for high quality)
+    /// * `mode` - Channel mode; [`StereoMode::DualChannel`] avoids joint-stereo
+    ///   artifacts when the left and right parts are unrelated
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(LameEncoder)` on success, or `Err(ConversionError)` with error message.
+    pub fn new_with_mode(sample_rate: u32, channels: u16, bitrate: u32, mode: StereoMode) -> Result<Self, ConversionError> {
+        Self::create(sample_rate, channels, bitrate, Some(mode), DEFAULT_QUALITY, None)
+    }
+
+    /// Creates the encoder, leaving the channel mode to LAME when `mode` is `None`
+    /// and encoding at the input rate when `out_sample_rate` is `None`
+    pub(crate) fn create(
+        sample_rate: u32,
+        channels: u16,
+        bitrate: u32,
+        mode: Option<StereoMode>,
+        quality: u8,
+        out_sample_rate: Option<u32>,
+    ) -> Result<Self, ConversionError> {
+        if quality > 9 {
+            return Err(ConversionError::InvalidArgument(format!("LAME quality must be between 0 and 9, got {}", quality)));
+        }
+        if let Some(out_sample_rate) = out_sample_rate {
+            validate_out_sample_rate(out_sample_rate)?;
+        }
+
+        let mut encoder = LameEncoder {
+            lame: std::ptr::null_mut(),
+            sample_rate,
+            channels,
+            out_sample_rate,
+            bitrate,
+            mode,
+            quality,
+            error_protection: false,
+            disable_reservoir: false,
+        };
+        encoder.lame = encoder.init_lame()?;
+        Ok(encoder)
+    }
+
+    /// Creates and configures a LAME state from the encoder's settings
+    fn init_lame(&self) -> Result<LameT, ConversionError> {
+        let (sample_rate, channels, bitrate, mode, quality) = (self.sample_rate, self.channels, self.bitrate, self.mode, self.quality);
         unsafe {
             let lame = lame_init();
             if lame.is_null() {
-                return Err("Failed to initialize LAME encoder".to_string());
+                return Err(ConversionError::LameInit("Failed to initialize LAME encoder".to_string()));
             }
 
             lame_set_in_samplerate(lame, sample_rate as c_int);
             lame_set_num_channels(lame, channels as c_int);
-            lame_set_out_samplerate(lame, sample_rate as c_int);
+            lame_set_out_samplerate(lame, self.out_sample_rate.unwrap_or(sample_rate) as c_int);
             lame_set_brate(lame, bitrate as c_int);
-            lame_set_quality(lame, 0); // Highest quality (0 is best, 9 is worst)
+            lame_set_quality(lame, quality as c_int); // 0 is best, 9 is worst
+            if self.error_protection && lame_set_error_protection(lame, 1) != 0 {
+                lame_close(lame);
+                return Err(ConversionError::LameInit("Failed to enable CRC error protection".to_string()));
+            }
+            if self.disable_reservoir && lame_set_disable_reservoir(lame, 1) != 0 {
+                lame_close(lame);
+                return Err(ConversionError::LameInit("Failed to disable the bit reservoir".to_string()));
+            }
+            if let Some(mode) = mode {
+                if lame_set_mode(lame, mode as c_int) != 0 {
+                    lame_close(lame);
+                    return Err(ConversionError::LameInit(format!("Failed to set channel mode {:?}", mode)));
+                }
+            }
 
             if lame_init_params(lame) != 0 {
                 lame_close(lame);
-                return Err("Failed to initialize LAME parameters".to_string());
+                return Err(ConversionError::LameInit("Failed to initialize LAME parameters".to_string()));
+            }
+
+            // LAME quietly substitutes the nearest legal value for a sample
+            // rate or bitrate the MPEG version cannot carry, e.g. 320 kbps at
+            // 22.05 kHz (MPEG-2 tops out at 160 kbps)
+            let out_sample_rate = self.out_sample_rate.unwrap_or(sample_rate);
+            let (actual_sample_rate, actual_bitrate) = (lame_get_out_samplerate(lame), lame_get_brate(lame));
+            if actual_sample_rate != out_sample_rate as c_int {
+                lame_close(lame);
+                return Err(ConversionError::LameInit(format!(
+                    "LAME cannot encode at {} Hz (it would use {} Hz)",
+                    out_sample_rate, actual_sample_rate
+                )));
+            }
+            if actual_bitrate != bitrate as c_int {
+                lame_close(lame);
+                return Err(ConversionError::LameInit(format!(
+                    "{} kbps is not a valid MP3 bitrate at {} Hz (LAME would use {} kbps)",
+                    bitrate, out_sample_rate, actual_bitrate
+                )));
             }
 
-            Ok(LameEncoder { lame })
+            Ok(lame)
+        }
+    }
+
+    /// Prepares the encoder for a new MP3 stream with the same settings
+    /// 
+    /// LAME cannot start another stream once it has been flushed, so the LAME
+    /// state is recreated; the settings given at construction are kept.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn reset(&mut self) -> Result<(), ConversionError> {
+        let lame = self.init_lame()?;
+        unsafe {
+            lame_close(self.lame);
+        }
+        self.lame = lame;
+        Ok(())
+    }
+
+    /// Adds a CRC-16 checksum to every MP3 frame
+    /// 
+    /// Decoders use the checksum to detect corrupted frame headers and side
+    /// information, which helps verify archived files. The 2 bytes per frame
+    /// come out of the audio budget rather than growing the file: at 44.1 kHz
+    /// that is about 0.6 kbps, or 0.3% of a 192 kbps stream.
+    /// 
+    /// The LAME state is recreated as by [`LameEncoder::reset`], so call this
+    /// before encoding a stream, not in the middle of one.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - `true` to write CRC-protected frames
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_error_protection(&mut self, enabled: bool) -> Result<(), ConversionError> {
+        self.error_protection = enabled;
+        self.reset()
+    }
+
+    /// Turns off the bit reservoir, making every MP3 frame self-contained
+    /// 
+    /// Normally a frame may store part of its audio data in space left over
+    /// by earlier frames. Decoders that start mid-stream or splice streams,
+    /// as some streaming players do, then cannot decode the first frames
+    /// they see. Without the reservoir each frame carries only its own data.
+    /// At a constant bitrate the file size is unchanged; the cost is slightly
+    /// lower quality on passages that would have borrowed bits.
+    /// 
+    /// The LAME state is recreated as by [`LameEncoder::reset`], so call this
+    /// before encoding a stream, not in the middle of one.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `disabled` - `true` to encode without the bit reservoir
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_disable_reservoir(&mut self, disabled: bool) -> Result<(), ConversionError> {
+        self.disable_reservoir = disabled;
+        self.reset()
+    }
+
+    /// Checks that audio with the given format can be fed to this encoder
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if the sample rate and channel count match the encoder's,
+    /// or `Err(ConversionError)` describing the mismatch.
+    pub fn check_format(&self, sample_rate: u32, channels: u16) -> Result<(), ConversionError> {
+        if sample_rate != self.sample_rate || channels != self.channels {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Audio format {} Hz, {} channels does not match the encoder's {} Hz, {} channels",
+                sample_rate, channels, self.sample_rate, self.channels
+            )));
         }
+        Ok(())
+    }
+
+    /// Returns the input sample rate in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the number of input channels
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Returns the sample rate of the MP3 stream in Hz
+    pub fn out_sample_rate(&self) -> u32 {
+        self.out_sample_rate.unwrap_or(self.sample_rate)
     }
 
     pub fn encode_buffer(
@@ -101,9 +393,9 @@ impl LameEncoder {
         left: &[i16],
         right: &[i16],
         mp3_buffer: &mut [u8],
-    ) -> Result<usize, String> {
+    ) -> Result<usize, ConversionError> {
         if left.len() != right.len() {
-            return Err("Left and right channel buffers must have the same length".to_string());
+            return Err(ConversionError::InvalidArgument("Left and right channel buffers must have the same length".to_string()));
         }
 
         unsafe {
@@ -117,14 +409,55 @@ impl LameEncoder {
             );
 
             if result < 0 {
-                Err("LAME encoding error".to_string())
+                Err(ConversionError::Encoding("LAME encoding error".to_string()))
+            } else {
+                Ok(result as usize)
+            }
+        }
+    }
+
+    /// Encodes interleaved stereo samples (L, R, L, R, ...) without splitting them
+    /// 
+    /// Only available for encoders created with 2 channels.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pcm` - Interleaved stereo samples, a whole number of frames
+    /// * `mp3_buffer` - Output buffer, see [`lame_mp3_buffer_size`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(usize)` with the number of MP3 bytes written to `mp3_buffer`,
+    /// or `Err(ConversionError)` with error message.
+    pub fn encode_buffer_interleaved(&mut self, pcm: &[i16], mp3_buffer: &mut [u8]) -> Result<usize, ConversionError> {
+        if self.channels != 2 {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Interleaved encoding needs a stereo encoder, this one has {} channel(s)",
+                self.channels
+            )));
+        }
+        if !pcm.len().is_multiple_of(2) {
+            return Err(ConversionError::InvalidArgument("Interleaved stereo buffer ends partway through a frame".to_string()));
+        }
+
+        unsafe {
+            let result = lame_encode_buffer_interleaved(
+                self.lame,
+                pcm.as_ptr(),
+                (pcm.len() / 2) as c_int,
+                mp3_buffer.as_mut_ptr(),
+                mp3_buffer.len() as c_int,
+            );
+
+            if result < 0 {
+                Err(ConversionError::Encoding("LAME encoding error".to_string()))
             } else {
                 Ok(result as usize)
             }
         }
     }
 
-    pub fn flush(&mut self, mp3_buffer: &mut [u8]) -> Result<usize, String> {
+    pub fn flush(&mut self, mp3_buffer: &mut [u8]) -> Result<usize, ConversionError> {
         unsafe {
             let result = lame_encode_flush(
                 self.lame,
@@ -133,7 +466,7 @@ impl LameEncoder {
             );
 
             if result < 0 {
-                Err("LAME flush error".to_string())
+                Err(ConversionError::Encoding("LAME flush error".to_string()))
             } else {
                 Ok(result as usize)
             }
@@ -34,7 +34,8 @@
  * ```
  */
 
-use std::os::raw::{c_char, c_int};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
 
 // FluidSynth FFI bindings
 // These structures are opaque and only accessed through pointers
@@ -57,10 +58,31 @@ pub struct fluid_player_t {
     _private: [u8; 0],
 }
 
+/// FluidSynth loaded SoundFont structure
+#[repr(C)]
+pub struct fluid_sfont_t {
+    _private: [u8; 0],
+}
+
+/// FluidSynth SoundFont preset structure
+#[repr(C)]
+pub struct fluid_preset_t {
+    _private: [u8; 0],
+}
+
+
+/// FluidSynth chorus modulation waveform: sine
+pub const FLUID_CHORUS_MOD_SINE: i32 = 0;
+
+/// FluidSynth chorus modulation waveform: triangle
+pub const FLUID_CHORUS_MOD_TRIANGLE: i32 = 1;
 
 /// FluidSynth player status: currently playing
 pub const FLUID_PLAYER_PLAYING: u32 = 1;
 
+/// FluidSynth player tempo type: multiplier applied to the MIDI file's own tempo
+pub const FLUID_PLAYER_TEMPO_INTERNAL: i32 = 0;
+
 #[link(name = "fluidsynth")]
 unsafe extern "C" {
     pub fn new_fluid_settings() -> *mut fluid_settings_t;
@@ -72,22 +94,96 @@ unsafe extern "C" {
     pub fn new_fluid_synth(settings: *mut fluid_settings_t) -> *mut fluid_synth_t;
     pub fn delete_fluid_synth(synth: *mut fluid_synth_t);
     pub fn fluid_synth_sfload(synth: *mut fluid_synth_t, filename: *const c_char, reset_presets: c_int) -> c_int;
+    pub fn fluid_synth_sfunload(synth: *mut fluid_synth_t, id: c_int, reset_presets: c_int) -> c_int;
+    pub fn fluid_synth_set_chorus_type(synth: *mut fluid_synth_t, type_: c_int) -> c_int;
+    pub fn fluid_synth_set_polyphony(synth: *mut fluid_synth_t, polyphony: c_int) -> c_int;
+    pub fn fluid_synth_all_sounds_off(synth: *mut fluid_synth_t, chan: c_int) -> c_int;
+    pub fn fluid_synth_get_sfont_by_id(synth: *mut fluid_synth_t, id: c_int) -> *mut fluid_sfont_t;
+    
+    // SoundFont preset enumeration
+    pub fn fluid_sfont_iteration_start(sfont: *mut fluid_sfont_t);
+    pub fn fluid_sfont_iteration_next(sfont: *mut fluid_sfont_t) -> *mut fluid_preset_t;
+    pub fn fluid_preset_get_name(preset: *mut fluid_preset_t) -> *const c_char;
+    pub fn fluid_preset_get_banknum(preset: *mut fluid_preset_t) -> c_int;
+    pub fn fluid_preset_get_num(preset: *mut fluid_preset_t) -> c_int;
     
     pub fn new_fluid_player(synth: *mut fluid_synth_t) -> *mut fluid_player_t;
     pub fn delete_fluid_player(player: *mut fluid_player_t);
     pub fn fluid_player_add(player: *mut fluid_player_t, midifile: *const c_char) -> c_int;
+    pub fn fluid_player_add_mem(player: *mut fluid_player_t, buffer: *const c_void, len: usize) -> c_int;
     pub fn fluid_player_play(player: *mut fluid_player_t) -> c_int;
     pub fn fluid_player_get_status(player: *mut fluid_player_t) -> c_int;
+    pub fn fluid_player_get_current_tick(player: *mut fluid_player_t) -> c_int;
+    pub fn fluid_player_get_total_ticks(player: *mut fluid_player_t) -> c_int;
+    pub fn fluid_player_set_tempo(player: *mut fluid_player_t, tempo_type: c_int, tempo: f64) -> c_int;
     
     // Audio synthesis functions
     pub fn fluid_synth_write_s16(synth: *mut fluid_synth_t, len: c_int, lbuf: *mut i16, loff: c_int, lincr: c_int, rbuf: *mut i16, roff: c_int, rincr: c_int) -> c_int;
+    pub fn fluid_synth_write_float(synth: *mut fluid_synth_t, len: c_int, lout: *mut c_void, loff: c_int, lincr: c_int, rout: *mut c_void, roff: c_int, rincr: c_int) -> c_int;
     
     // Program change function
     pub fn fluid_synth_program_change(synth: *mut fluid_synth_t, chan: c_int, program: c_int) -> c_int;
+    pub fn fluid_synth_bank_select(synth: *mut fluid_synth_t, chan: c_int, bank: c_int) -> c_int;
+    
+    // Library information
+    pub fn fluid_version_str() -> *mut c_char;
+}
+
+/// Versions of the native libraries this crate is linked against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryVersions {
+    /// FluidSynth version, e.g. "2.3.4"
+    pub fluidsynth: String,
+    /// LAME version, e.g. "3.100"
+    pub lame: String,
+}
+
+impl std::fmt::Display for LibraryVersions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FluidSynth {}, LAME {}", self.fluidsynth, self.lame)
+    }
+}
+
+/// Returns the versions of the FluidSynth and LAME libraries in use
+/// 
+/// These are the versions loaded at run time, which can differ from the
+/// headers the crate was built against; include them in bug reports.
+pub fn library_versions() -> LibraryVersions {
+    // Both functions return pointers to static strings owned by the library
+    let version = |ptr: *const c_char| {
+        if ptr.is_null() {
+            "unknown".to_string()
+        } else {
+            unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned()
+        }
+    };
+    LibraryVersions {
+        fluidsynth: version(unsafe { fluid_version_str() }),
+        lame: version(unsafe { lame_bindings::get_lame_version() }),
+    }
 }
 
+pub mod audio_utils;
+pub mod dither;
+pub mod error;
+#[cfg(feature = "capi")]
+pub mod ffi;
+#[cfg(feature = "flac")]
+pub mod flac_bindings;
+#[cfg(feature = "flac")]
+pub mod flac_encoder;
+pub mod fluid_player;
+pub mod fluid_settings;
+pub mod id3;
 pub mod midi_converter;
+pub mod midi_file;
+pub mod midi_info;
 pub mod mp3_encoder;
 pub mod lame_bindings;
 pub mod mml_converter;
-pub mod pipeline;
\ No newline at end of file
+pub mod mml_syntax;
+#[cfg(feature = "ogg")]
+pub mod ogg_encoder;
+pub mod pipeline;
+#[cfg(feature = "ogg")]
+pub mod vorbis_bindings;
\ No newline at end of file
@@ -6,75 +6,455 @@
  * It supports SoundFont (.sf2) files for high-quality synthesis.
  */
 
-use yks_converter_example::pipeline::ConversionPipeline;
-use std::env;
+use yks_converter_example::error::ConversionError;
+use yks_converter_example::lame_bindings;
+use yks_converter_example::midi_converter::{self, MidiConverter};
+use yks_converter_example::midi_file::MidiFile;
+use yks_converter_example::midi_info::read_midi_info;
+use yks_converter_example::mml_converter::{self, MmlConverter};
+use yks_converter_example::mp3_encoder::{self, Mp3Encoder, Mp3EncoderConfig};
+use yks_converter_example::pipeline::{self, ConversionPipeline, InputKind, OutputFormat};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use std::fs;
+use std::io::{self, Read, Write};
 use std::path::Path;
 
+/// Path that stands for stdin as input and stdout as output
+const STDIO_PATH: &str = "-";
+
+/// Usage examples shown after the option list in `--help`
+const EXAMPLES: &str = "\
+Examples:
+  yks-converter-example song.mml soundfont.sf2 output.mp3
+  yks-converter-example song.mml soundfont.sf2 output.mp3 25   # Use instrument 25
+  yks-converter-example song.mid soundfont.sf2 output.mp3
+  yks-converter-example --bitrate 320 song.mml soundfont.sf2 output.mp3
+  yks-converter-example -i song.mml -s soundfont.sf2 -o output.mp3 --instrument 40
+  yks-converter-example song.mml soundfont.sf2 output.ogg      # Encode OGG Vorbis instead
+  yks-converter-example song.mml soundfont.sf2 output.flac     # Lossless FLAC
+  yks-converter-example song.mml soundfont.sf2 output.wav      # Uncompressed WAV
+  yks-converter-example songs/ soundfont.sf2 mp3s/              # Convert every .mml in songs/
+  yks-converter-example --jobs 8 songs/ soundfont.sf2 mp3s/     # ... using 8 threads
+  yks-converter-example --list-instruments soundfont.sf2        # Show available instruments
+  yks-converter-example --info song.mid                         # Show tracks and tempo map
+  yks-converter-example --lint song.mml                         # Look for notes that never stop
+  cat song.mml | yks-converter-example - soundfont.sf2 - > output.mp3
+  yks-converter-example --polyphony 1024 song.mid orchestra.sf2 output.mp3
+  yks-converter-example --preview 10 song.mml soundfont.sf2 preview.mp3  # First 10 seconds only
+  yks-converter-example --title \"Song\" --artist \"Bard\" song.mml soundfont.sf2 output.mp3";
+
+/// YKS Converter Example - MML/MIDI to MP3 converter
+/// 
+/// Input, SoundFont, output, and instrument can be given positionally
+/// (`<input> <sf2> <output> [instrument]`) or as named flags.
+#[derive(Parser)]
+#[command(version, after_help = EXAMPLES)]
+struct Cli {
+    /// Input MML file (.mml), MIDI file (.mid, .midi), a directory of MML files,
+    /// or `-` to read MML from stdin
+    #[arg(value_name = "INPUT_FILE")]
+    input_file: Option<String>,
+
+    /// SoundFont file (.sf2)
+    #[arg(value_name = "SF2_FILE")]
+    sf2_file: Option<String>,
+
+    /// Output MP3 or WAV file (.ogg/.flac with the `ogg`/`flac` features),
+    /// output directory for batch conversion, or `-` to write MP3 to stdout
+    #[arg(value_name = "OUTPUT_FILE")]
+    output_file: Option<String>,
+
+    /// MIDI instrument number for MML input (0-127) [default: 0]
+    #[arg(value_name = "INSTRUMENT", value_parser = clap::value_parser!(u8).range(0..=127))]
+    instrument_number: Option<u8>,
+
+    /// Input file, instead of the first positional argument
+    #[arg(short, long, value_name = "FILE", conflicts_with = "input_file")]
+    input: Option<String>,
+
+    /// SoundFont file, instead of the second positional argument
+    #[arg(short, long, value_name = "FILE", conflicts_with = "sf2_file")]
+    soundfont: Option<String>,
+
+    /// Output file or directory, instead of the third positional argument
+    #[arg(short, long, value_name = "FILE", conflicts_with = "output_file")]
+    output: Option<String>,
+
+    /// MIDI instrument number, instead of the fourth positional argument
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=127), conflicts_with = "instrument_number")]
+    instrument: Option<u8>,
+
+    /// Print the presets of a SoundFont and exit
+    #[arg(long, value_name = "SF2_FILE", exclusive = true)]
+    list_instruments: Option<String>,
+
+    /// Print the format, track names, and tempo map of a MIDI file
+    /// (or basic information about an MML file) and exit
+    #[arg(long, value_name = "FILE", exclusive = true)]
+    info: Option<String>,
+
+    /// Check a MIDI file, or the MIDI generated from an MML file, for stuck
+    /// or silenced notes and exit
+    #[arg(long, value_name = "FILE", exclusive = true)]
+    lint: Option<String>,
+
+    /// Print the linked FluidSynth and LAME versions and exit
+    #[arg(long, exclusive = true)]
+    version_libs: bool,
+
+    /// MP3 bitrate in kbps
+    #[arg(long, value_name = "KBPS", default_value_t = mp3_encoder::DEFAULT_BITRATE, value_parser = parse_bitrate)]
+    bitrate: u32,
+
+    /// LAME quality (0 is best/slowest)
+    #[arg(long, value_name = "N", default_value_t = lame_bindings::DEFAULT_QUALITY, value_parser = clap::value_parser!(u8).range(0..=9))]
+    encode_quality: u8,
+
+    /// Downmix to a mono MP3
+    #[arg(long)]
+    mono: bool,
+
+    /// Add a CRC checksum to every MP3 frame, for verifying archived files
+    #[arg(long)]
+    crc: bool,
+
+    /// Make every MP3 frame self-contained, for streaming decoders that
+    /// cannot handle the bit reservoir
+    #[arg(long)]
+    no_reservoir: bool,
+
+    /// Resample the MP3 (e.g. 22050) [default: synthesis rate]
+    #[arg(long, value_name = "HZ", value_parser = parse_out_rate)]
+    out_rate: Option<u32>,
+
+    /// Synthesizer gain (0.0-10.0)
+    #[arg(long, value_name = "FACTOR", default_value_t = midi_converter::DEFAULT_GAIN)]
+    gain: f64,
+
+    /// Maximum simultaneous voices
+    #[arg(long, value_name = "N", default_value_t = midi_converter::DEFAULT_POLYPHONY,
+          value_parser = clap::value_parser!(u32).range(1..=midi_converter::MAX_POLYPHONY as i64))]
+    polyphony: u32,
+
+    /// Worker threads for directory conversion
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    jobs: u64,
+
+    /// Render only the first SECONDS of MML input to a quick MP3 preview
+    #[arg(long, value_name = "SECONDS", value_parser = parse_preview)]
+    preview: Option<f64>,
+
+    /// Keep the intermediate MIDI/WAV files and print their paths
+    #[arg(long)]
+    keep_temp: bool,
+
+    /// Title written to the MP3's ID3 tag
+    #[arg(long, value_name = "TEXT")]
+    title: Option<String>,
+
+    /// Artist written to the MP3's ID3 tag
+    #[arg(long, value_name = "TEXT")]
+    artist: Option<String>,
+}
+
+/// Options shared by every conversion, resolved from [`Cli`]
+struct CliOptions {
+    encoder_config: Mp3EncoderConfig,
+    gain: f64,
+    polyphony: u32,
+    jobs: usize,
+    keep_temp: bool,
+    preview: Option<f64>,
+}
+
+/// Files and instrument for a single conversion, resolved from [`Cli`]
+struct ConversionArgs {
+    input_path: String,
+    sf2_path: String,
+    output_path: String,
+    instrument_number: u8,
+}
+
+impl Cli {
+    /// Splits the parsed arguments into options and the conversion's files,
+    /// exiting with a usage error if a required file is missing
+    fn resolve(self) -> (CliOptions, ConversionArgs) {
+        let required = |value: Option<String>, name: &str| {
+            value.unwrap_or_else(|| {
+                Cli::command()
+                    .error(ErrorKind::MissingRequiredArgument, format!("missing {}", name))
+                    .exit()
+            })
+        };
+        let args = ConversionArgs {
+            input_path: required(self.input.or(self.input_file), "<INPUT_FILE> (or --input)"),
+            sf2_path: required(self.soundfont.or(self.sf2_file), "<SF2_FILE> (or --soundfont)"),
+            output_path: required(self.output.or(self.output_file), "<OUTPUT_FILE> (or --output)"),
+            instrument_number: self.instrument.or(self.instrument_number).unwrap_or(0),
+        };
+
+        let encoder_config = Mp3EncoderConfig {
+            bitrate: self.bitrate,
+            quality: self.encode_quality,
+            force_mono: self.mono,
+            error_protection: self.crc,
+            disable_reservoir: self.no_reservoir,
+            out_sample_rate: self.out_rate,
+            title: self.title,
+            artist: self.artist,
+            ..Mp3EncoderConfig::default()
+        };
+        let options = CliOptions {
+            encoder_config,
+            gain: self.gain,
+            polyphony: self.polyphony,
+            jobs: self.jobs as usize,
+            keep_temp: self.keep_temp,
+            preview: self.preview,
+        };
+        (options, args)
+    }
+}
+
+/// Parses an MP3 bitrate, accepting only bitrates the encoder supports
+fn parse_bitrate(value: &str) -> Result<u32, String> {
+    let bitrate = value.parse().map_err(|_| format!("Invalid bitrate: {}", value))?;
+    Mp3Encoder::validate_bitrate(bitrate).map_err(|e| e.to_string())?;
+    Ok(bitrate)
+}
+
+/// Parses a preview length in seconds
+fn parse_preview(value: &str) -> Result<f64, String> {
+    let seconds = value.parse().map_err(|_| format!("Invalid preview length: {}", value))?;
+    pipeline::validate_preview_secs(seconds).map_err(|e| e.to_string())?;
+    Ok(seconds)
+}
+
+/// Parses an MP3 output sample rate, accepting only legal MP3 rates
+fn parse_out_rate(value: &str) -> Result<u32, String> {
+    let out_rate = value.parse().map_err(|_| format!("Invalid output sample rate: {}", value))?;
+    lame_bindings::validate_out_sample_rate(out_rate).map_err(|e| e.to_string())?;
+    Ok(out_rate)
+}
+
+/// Prints the bank, program, and name of every preset in `sf2_path`
+fn list_instruments(sf2_path: &str) -> Result<(), ConversionError> {
+    let mut converter = MidiConverter::new()?;
+    converter.load_soundfont(sf2_path)?;
+
+    let presets = converter.list_presets()?;
+    println!("🎹 {} presets in {}", presets.len(), sf2_path);
+    println!("  Bank  Prog  Name");
+    for preset in &presets {
+        println!("  {:>4}  {:>4}  {}", preset.bank, preset.program, preset.name);
+    }
+    Ok(())
+}
+
+/// Prints information about a MIDI or MML file
+fn print_file_info(path: &str) -> Result<(), ConversionError> {
+    if InputKind::detect(path) == InputKind::Midi {
+        println!("{}", read_midi_info(path)?);
+    } else {
+        println!("{}", ConversionPipeline::new()?.get_conversion_info(path)?);
+    }
+    Ok(())
+}
+
+/// Prints the lints of a MIDI file or of the MIDI generated from an MML file
+fn print_lints(path: &str) -> Result<(), String> {
+    let lints = if InputKind::detect(path) == InputKind::Midi {
+        let midi_data = fs::read(path).map_err(|e| format!("Failed to read MIDI file: {}", e))?;
+        MidiFile::parse(&midi_data).map_err(|e| e.to_string())?.lint()
+    } else {
+        let mml_text = mml_converter::read_mml_file(path).map_err(|e| format!("Failed to read MML file: {}", e))?;
+        MmlConverter::new().lint_midi(&mml_text).map_err(|e| e.to_string())?
+    };
+
+    if lints.is_empty() {
+        println!("✅ No problems found in {}", path);
+    }
+    for lint in &lints {
+        eprintln!("⚠️  Warning: {}", lint);
+    }
+    Ok(())
+}
+
+/// Converts with `-` as input (MML from stdin) and/or output (MP3 to stdout)
+/// 
+/// Everything stays in memory, and progress goes to stderr so stdout carries
+/// nothing but the MP3 stream.
+fn convert_streaming(args: &ConversionArgs, options: &CliOptions) -> Result<(), String> {
+    if options.preview.is_some() {
+        return Err("--preview is not supported when reading from stdin or writing to stdout".to_string());
+    }
+
+    let to_stdout = args.output_path == STDIO_PATH;
+    if !to_stdout && OutputFormat::from_path(&args.output_path).map_err(|e| e.to_string())? != OutputFormat::Mp3 {
+        return Err("Only MP3 output is supported when reading from stdin".to_string());
+    }
+
+    let is_midi = args.input_path != STDIO_PATH && InputKind::detect(&args.input_path) == InputKind::Midi;
+
+    eprintln!("🎵 YKS Converter Example - Streaming {} to MP3...", if is_midi { "MIDI" } else { "MML" });
+
+    let mut pipeline = ConversionPipeline::new().map_err(|e| format!("Failed to create conversion pipeline: {}", e))?;
+    pipeline.set_encoder_config(options.encoder_config.clone()).map_err(|e| format!("Failed to configure MP3 encoder: {}", e))?;
+    pipeline.set_gain(options.gain).map_err(|e| format!("Failed to set gain: {}", e))?;
+    pipeline.set_polyphony(options.polyphony).map_err(|e| format!("Failed to set polyphony: {}", e))?;
+    pipeline.load_soundfont(&args.sf2_path).map_err(|e| format!("Failed to load soundfont: {}", e))?;
+    pipeline.set_instrument(args.instrument_number).map_err(|e| format!("Failed to set instrument: {}", e))?;
+
+    let mp3_data = if is_midi {
+        let midi_data = fs::read(&args.input_path).map_err(|e| format!("Failed to read MIDI file: {}", e))?;
+        pipeline.convert_midi_bytes_to_mp3_bytes(&midi_data)
+    } else {
+        let mml_text = if args.input_path == STDIO_PATH {
+            let mut mml_data = Vec::new();
+            io::stdin().read_to_end(&mut mml_data)
+                .and_then(|_| mml_converter::decode_mml_text(&mml_data))
+                .map_err(|e| format!("Failed to read MML from stdin: {}", e))?
+        } else {
+            mml_converter::read_mml_file(&args.input_path).map_err(|e| format!("Failed to read MML file: {}", e))?
+        };
+        pipeline.convert_mml_text_to_mp3_bytes(&mml_text)
+    }.map_err(|e| format!("Conversion failed: {}", e))?;
+
+    if to_stdout {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&mp3_data)
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("Failed to write MP3 to stdout: {}", e))?;
+    } else {
+        fs::write(&args.output_path, &mp3_data).map_err(|e| format!("Failed to write MP3 file: {}", e))?;
+    }
+    eprintln!("🎉 Conversion completed successfully! ({} bytes)", mp3_data.len());
+    Ok(())
+}
+
+/// Converts every MML file in `input_dir` and prints a summary, exiting
+/// with an error status if any file failed
+fn convert_directory(pipeline: &mut ConversionPipeline, input_dir: &str, output_dir: &str, jobs: usize) {
+    let report = if jobs > 1 {
+        pipeline.convert_mml_directory_parallel(input_dir, output_dir, jobs)
+    } else {
+        pipeline.convert_mml_directory(input_dir, output_dir)
+    };
+    let report = match report {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ Batch conversion failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let failures: Vec<_> = report.iter().filter(|(_, result)| result.is_err()).collect();
+    println!();
+    println!("📊 Batch summary: {} converted, {} failed", report.len() - failures.len(), failures.len());
+    for (path, result) in &failures {
+        if let Err(e) = result {
+            eprintln!("❌ {}: {}", path.display(), e);
+        }
+    }
+    println!("📁 Output saved to: {}", output_dir);
+
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    
-    // Check command line arguments
-    if args.len() != 4 && args.len() != 5 {
-        eprintln!("YKS Converter Example - MML/MIDI to MP3 Converter");
-        eprintln!("Usage: {} <input_file> <sf2_file> <output_mp3> [instrument_number]", args[0]);
-        eprintln!();
-        eprintln!("Arguments:");
-        eprintln!("  input_file        - Input MML file (.mml) or MIDI file (.mid, .midi)");
-        eprintln!("  sf2_file          - SoundFont file (.sf2)");
-        eprintln!("  output_mp3        - Output MP3 file");
-        eprintln!("  instrument_number - Optional: MIDI instrument number (0-127, default: 0)");
-        eprintln!();
-        eprintln!("Examples:");
-        eprintln!("  {} song.mml soundfont.sf2 output.mp3", args[0]);
-        eprintln!("  {} song.mml soundfont.sf2 output.mp3 1    # Use instrument 1", args[0]);
-        eprintln!("  {} song.mml soundfont.sf2 output.mp3 25   # Use instrument 25", args[0]);
-        eprintln!("  {} song.mid soundfont.sf2 output.mp3 40   # Use instrument 40", args[0]);
+    let cli = Cli::parse();
+
+    if cli.version_libs {
+        let versions = yks_converter_example::library_versions();
+        println!("yks-converter-example {}", env!("CARGO_PKG_VERSION"));
+        println!("FluidSynth {}", versions.fluidsynth);
+        println!("LAME {}", versions.lame);
+        return;
+    }
+
+    if let Some(sf2_path) = &cli.list_instruments {
+        if let Err(e) = list_instruments(sf2_path) {
+            eprintln!("❌ Failed to list instruments: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = &cli.info {
+        if let Err(e) = print_file_info(path) {
+            eprintln!("❌ Failed to read file info: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = &cli.lint {
+        if let Err(e) = print_lints(path) {
+            eprintln!("❌ Failed to lint file: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let (options, args) = cli.resolve();
+    if args.input_path == STDIO_PATH || args.output_path == STDIO_PATH {
+        if let Err(e) = convert_streaming(&args, &options) {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let input_path = &args.input_path;
+    let sf2_path = &args.sf2_path;
+    let mp3_path = &args.output_path;
+    let instrument_number = args.instrument_number;
+
+    // Detect input file type
+    let is_batch = Path::new(input_path).is_dir();
+    let input_kind = if is_batch { InputKind::Mml } else { InputKind::detect(input_path) };
+    let is_mml = input_kind == InputKind::Mml;
+
+    if input_kind == InputKind::Unknown {
+        eprintln!("❌ Unsupported file format: {}", input_path);
+        eprintln!("   Supported formats: .mml, .mid, .midi");
         std::process::exit(1);
     }
 
-    let input_path = &args[1];
-    let sf2_path = &args[2];
-    let mp3_path = &args[3];
-    let instrument_number = if args.len() == 5 {
-        match args[4].parse::<u8>() {
-            Ok(num) if num <= 127 => num,
-            Ok(_) => {
-                eprintln!("❌ Instrument number must be between 0-127");
-                std::process::exit(1);
-            },
-            Err(_) => {
-                eprintln!("❌ Invalid instrument number: {}", args[4]);
+    // Pick the encoder from the output extension
+    let format = if is_batch {
+        OutputFormat::Mp3
+    } else {
+        match OutputFormat::from_path(mp3_path) {
+            Ok(format) => format,
+            Err(e) => {
+                eprintln!("❌ {}", e);
                 std::process::exit(1);
             }
         }
-    } else {
-        0 // Default to instrument 0 (Grand Piano)
     };
+    let format_name = format.extension().to_uppercase();
 
-    // Detect input file type
-    let input_extension = Path::new(input_path)
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    let is_mml = input_extension == "mml";
-    let is_midi = matches!(input_extension.as_str(), "mid" | "midi");
-
-    if !is_mml && !is_midi {
-        eprintln!("❌ Unsupported file format: {}", input_extension);
-        eprintln!("   Supported formats: .mml, .mid, .midi");
+    if options.preview.is_some() && !(is_mml && !is_batch && format == OutputFormat::Mp3) {
+        eprintln!("❌ --preview needs a single MML input file and MP3 output");
         std::process::exit(1);
     }
 
-    println!("🎵 YKS Converter Example - Starting {} to MP3 conversion...", 
-             if is_mml { "MML" } else { "MIDI" });
+    println!("🎵 YKS Converter Example - Starting {} to {} conversion...", 
+             if is_mml { "MML" } else { "MIDI" }, format_name);
     println!("📂 Input file: {}", input_path);
     println!("🎹 SoundFont: {}", sf2_path);
     if is_mml {
         println!("🎼 Instrument: {}", instrument_number);
     }
-    println!("🎧 Output: {}", mp3_path);
+    if format == OutputFormat::Mp3 {
+        println!("🎧 Output: {} ({} kbps)", mp3_path, options.encoder_config.bitrate);
+    } else {
+        println!("🎧 Output: {}", mp3_path);
+    }
     println!();
     
     // Initialize conversion pipeline
@@ -89,6 +469,23 @@ fn main() {
         }
     };
 
+    if let Err(e) = pipeline.set_encoder_config(options.encoder_config.clone()) {
+        eprintln!("❌ Failed to configure MP3 encoder: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = pipeline.set_gain(options.gain) {
+        eprintln!("❌ Failed to set gain: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = pipeline.set_polyphony(options.polyphony) {
+        eprintln!("❌ Failed to set polyphony: {}", e);
+        std::process::exit(1);
+    }
+
+    pipeline.set_keep_intermediates(options.keep_temp);
+
     // Load SoundFont file
     if let Err(e) = pipeline.load_soundfont(sf2_path) {
         eprintln!("❌ Failed to load soundfont: {}", e);
@@ -108,7 +505,7 @@ fn main() {
     }
 
     // Show file information for MML files
-    if is_mml {
+    if is_mml && !is_batch {
         match pipeline.get_conversion_info(input_path) {
             Ok(info) => println!("{}", info),
             Err(e) => eprintln!("⚠️  Warning: Could not get file info: {}", e),
@@ -116,50 +513,15 @@ fn main() {
         println!();
     }
 
-    // Convert file to MP3
-    let result = if is_mml {
-        pipeline.convert_mml_to_mp3(input_path, mp3_path)
-    } else {
-        // For MIDI files, use the existing pipeline but skip MML conversion step
-        use yks_converter_example::midi_converter::MidiConverter;
-        use yks_converter_example::mp3_encoder::Mp3Encoder;
-        
-        let temp_wav_path = "temp_conversion.wav";
-        
-        println!("🎹 Synthesizing MIDI to WAV...");
-        match MidiConverter::new() {
-            Ok(mut midi_converter) => {
-                match midi_converter.load_soundfont(sf2_path) {
-                    Ok(_) => {
-                        // MIDI files already contain instrument information
-                        // The instrument_number parameter is ignored for MIDI files
-                        match midi_converter.convert_midi_to_wav(input_path, temp_wav_path) {
-                            Ok(_) => {
-                                println!("✅ WAV file generated");
-
-                                println!("🎵 Encoding WAV to MP3...");
-                                match Mp3Encoder::convert_wav_to_mp3(temp_wav_path, mp3_path) {
-                                    Ok(_) => {
-                                        println!("✅ MP3 encoding completed");
-
-                                        // Clean up temporary file
-                                        if std::fs::remove_file(temp_wav_path).is_ok() {
-                                            println!("🧹 Cleaned up temporary file: {}", temp_wav_path);
-                                        }
-                                        
-                                        Ok(())
-                                    },
-                                    Err(e) => Err(format!("WAV to MP3 error: {}", e))
-                                }
-                            },
-                            Err(e) => Err(format!("MIDI to WAV error: {}", e))
-                        }
-                    },
-                    Err(e) => Err(format!("SoundFont error: {}", e))
-                }
-            },
-            Err(e) => Err(format!("MIDI converter error: {}", e))
-        }
+    if is_batch {
+        convert_directory(&mut pipeline, input_path, mp3_path, options.jobs);
+        return;
+    }
+
+    // Convert file to the output format
+    let result = match options.preview {
+        Some(seconds) => pipeline.convert_mml_preview(input_path, mp3_path, seconds),
+        None => pipeline.convert_any(input_path, mp3_path),
     };
 
     match result {
@@ -6,8 +6,217 @@
  */
 
 use crate::*;
+use crate::error::ConversionError;
+use crate::fluid_player::FluidPlayer;
+use crate::fluid_settings::FluidSettings;
+use crate::midi_file::{self, MidiFile};
 use hound::{WavSpec, WavWriter};
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default output sample rate used for synthesis
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+/// Default number of frames synthesized per buffer, see [`MidiConverter::set_buffer_size`]
+pub const BUFFER_SIZE: usize = 4096;
+
+/// Largest buffer accepted by [`MidiConverter::set_buffer_size`], in frames
+pub const MAX_BUFFER_SIZE: usize = 65536;
+
+/// Default synthesizer gain
+pub const DEFAULT_GAIN: f64 = 1.0;
+
+/// Default number of voices the synthesizer can play at once
+pub const DEFAULT_POLYPHONY: u32 = 256;
+
+/// Largest polyphony FluidSynth accepts
+pub const MAX_POLYPHONY: u32 = 65535;
+
+/// Longest release tail accepted by [`MidiConverter::set_release_tail`], in milliseconds
+pub const MAX_TAIL_MS: u32 = 10_000;
+
+/// RMS level (in dBFS) below which synthesized audio counts as silent
+/// 
+/// Far below any audible music; only renders that produced no notes at all,
+/// or notes without samples in the SoundFont, fall under it.
+pub const SILENCE_RMS_DBFS: f64 = -70.0;
+
+/// A preset (instrument) provided by a loaded SoundFont
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetInfo {
+    /// MIDI bank number (128 is the percussion bank in General MIDI SoundFonts)
+    pub bank: u16,
+    /// MIDI program number within the bank
+    pub program: u8,
+    /// Preset name stored in the SoundFont
+    pub name: String,
+}
+
+/// Synthesis continued after the MIDI data ends, see [`MidiConverter::set_release_tail`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReleaseTail {
+    /// Length of the tail in milliseconds (0 ends the render with the last event)
+    pub tail_ms: u32,
+    /// Fade the tail out linearly, so it ends in silence even if the reverb has not decayed
+    pub fade: bool,
+}
+
+/// Level statistics collected while synthesizing
+/// 
+/// Samples at `i16::MAX` or `i16::MIN` are counted as clipped, since FluidSynth
+/// saturates to those values when the mix is too loud.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SynthReport {
+    /// Number of samples written, counting left and right separately
+    pub total_samples: u64,
+    /// Number of samples at full scale
+    pub clipped_samples: u64,
+    /// Largest absolute sample value
+    pub peak_amplitude: u16,
+    /// Sum of the squared sample values, for [`SynthReport::rms_dbfs`]
+    pub sum_of_squares: u128,
+}
+
+impl SynthReport {
+    /// Returns the fraction (0.0–1.0) of samples that clipped
+    pub fn clipping_ratio(&self) -> f64 {
+        if self.total_samples == 0 {
+            0.0
+        } else {
+            self.clipped_samples as f64 / self.total_samples as f64
+        }
+    }
+
+    /// Returns `true` if any sample clipped
+    pub fn is_clipping(&self) -> bool {
+        self.clipped_samples > 0
+    }
+
+    /// Returns the RMS level in dBFS, or negative infinity for digital silence
+    pub fn rms_dbfs(&self) -> f64 {
+        if self.total_samples == 0 {
+            return f64::NEG_INFINITY;
+        }
+        let rms = (self.sum_of_squares as f64 / self.total_samples as f64).sqrt() / i16::MAX as f64;
+        20.0 * rms.log10()
+    }
+
+    /// Returns `true` if the RMS level is below [`SILENCE_RMS_DBFS`]
+    pub fn is_silent(&self) -> bool {
+        self.rms_dbfs() < SILENCE_RMS_DBFS
+    }
+
+    /// Collects the statistics of already synthesized samples
+    pub fn from_samples(samples: &[i16]) -> Self {
+        let mut report = SynthReport::default();
+        for &sample in samples {
+            report.record(sample);
+        }
+        report
+    }
+
+    fn record(&mut self, sample: i16) {
+        self.total_samples += 1;
+        self.sum_of_squares += (sample as i32 * sample as i32) as u128;
+        if sample == i16::MAX || sample == i16::MIN {
+            self.clipped_samples += 1;
+        }
+        self.peak_amplitude = self.peak_amplitude.max(sample.unsigned_abs());
+    }
+}
+
+/// Largest positive 24-bit sample value
+const I24_MAX: f32 = 8_388_607.0;
+
+/// Sample type FluidSynth can render into
+trait SynthSample: Copy + Default {
+    /// Renders `len` frames into the left and right buffers
+    /// 
+    /// # Safety
+    /// 
+    /// `synth` must be a live synthesizer and both buffers must hold `len` samples.
+    unsafe fn write(synth: *mut fluid_synth_t, len: usize, left: &mut [Self], right: &mut [Self]) -> i32;
+
+    /// Multiplies the sample by `gain` (0.0 to 1.0)
+    fn scale(self, gain: f32) -> Self;
+}
+
+impl SynthSample for i16 {
+    unsafe fn write(synth: *mut fluid_synth_t, len: usize, left: &mut [Self], right: &mut [Self]) -> i32 {
+        fluid_synth_write_s16(synth, len as i32, left.as_mut_ptr(), 0, 1, right.as_mut_ptr(), 0, 1)
+    }
+
+    fn scale(self, gain: f32) -> Self {
+        (self as f32 * gain).round() as i16
+    }
+}
+
+impl SynthSample for f32 {
+    unsafe fn write(synth: *mut fluid_synth_t, len: usize, left: &mut [Self], right: &mut [Self]) -> i32 {
+        fluid_synth_write_float(
+            synth,
+            len as i32,
+            left.as_mut_ptr() as *mut c_void,
+            0,
+            1,
+            right.as_mut_ptr() as *mut c_void,
+            0,
+            1,
+        )
+    }
+
+    fn scale(self, gain: f32) -> Self {
+        self * gain
+    }
+}
+
+/// Point in time after which a conversion gives up
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+impl Deadline {
+    /// Starts counting `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Deadline { at: Instant::now() + timeout, timeout }
+    }
+}
+
+/// Reasons to stop synthesizing before the player finishes
+/// 
+/// Cancellation and the deadline are checked before every buffer; whichever
+/// applies first ends the render with its error. Reaching `max_frames` ends
+/// it successfully, with the last buffer cut to fit.
+#[derive(Default, Clone, Copy)]
+pub(crate) struct StopConditions<'a> {
+    pub cancel: Option<&'a AtomicBool>,
+    pub deadline: Option<Deadline>,
+    pub max_frames: Option<usize>,
+}
+
+impl StopConditions<'_> {
+    fn check(&self) -> Result<(), ConversionError> {
+        if self.cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            return Err(ConversionError::Cancelled);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline.at => Err(ConversionError::Timeout(deadline.timeout)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Where the player reads MIDI data from
+enum MidiSource<'a> {
+    File(&'a str),
+    Bytes(&'a [u8]),
+}
 
 /// High-quality MIDI converter using FluidSynth synthesis
 /// 
@@ -23,9 +232,32 @@ use std::ffi::CString;
 /// converter.load_soundfont("soundfont.sf2").unwrap();
 /// converter.convert_midi_to_wav("input.mid", "output.wav").unwrap();
 /// ```
+/// 
+/// A converter is `Send`, so it can be moved into a worker thread, but not
+/// `Sync`; give each thread its own converter.
+/// 
+/// ```no_run
+/// use yks_converter_example::midi_converter::MidiConverter;
+/// 
+/// let mut converter = MidiConverter::new().unwrap();
+/// converter.load_soundfont("soundfont.sf2").unwrap();
+/// std::thread::spawn(move || converter.convert_midi_to_wav("input.mid", "output.wav"))
+///     .join()
+///     .unwrap()
+///     .unwrap();
+/// ```
 pub struct MidiConverter {
-    settings: *mut fluid_settings_t,
+    settings: FluidSettings,
     synth: *mut fluid_synth_t,
+    sample_rate: u32,
+    soundfonts: Vec<i32>,
+    tempo_scale: f64,
+    muted_channels: u16,
+    transpose: i8,
+    metronome: Option<u8>,
+    release_tail: ReleaseTail,
+    buffer_size: usize,
+    warnings: Vec<String>,
 }
 
 impl MidiConverter {
@@ -34,40 +266,71 @@ impl MidiConverter {
     /// Initializes FluidSynth with high-quality settings:
     /// - Sample rate: 44.1 kHz
     /// - Stereo output (2 channels)
-    /// - High polyphony (256 voices)
+    /// - High polyphony ([`DEFAULT_POLYPHONY`] voices, see [`MidiConverter::set_polyphony`])
     /// - Reverb and chorus enabled
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(MidiConverter)` on success, or `Err(String)` with error message.
-    pub fn new() -> Result<Self, String> {
-        unsafe {
-            let settings = new_fluid_settings();
-            if settings.is_null() {
-                return Err("Failed to create FluidSynth settings".to_string());
-            }
+    /// Returns `Ok(MidiConverter)` on success, or `Err(ConversionError)` with error message.
+    pub fn new() -> Result<Self, ConversionError> {
+        Self::with_sample_rate(DEFAULT_SAMPLE_RATE)
+    }
+
+    /// Creates a new MIDI converter synthesizing at the given sample rate
+    /// 
+    /// The rate is applied to FluidSynth before the synthesizer is created and
+    /// written into the WAV header, so it flows through to MP3 encoding.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample_rate` - Output sample rate in Hz (8000-96000), e.g. 48000 for video
+    ///   or 22050 for small voice clips
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(MidiConverter)` on success, or `Err(ConversionError)` with error message.
+    pub fn with_sample_rate(sample_rate: u32) -> Result<Self, ConversionError> {
+        if !(8000..=96000).contains(&sample_rate) {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Sample rate must be between 8000 and 96000 Hz, got {}",
+                sample_rate
+            )));
+        }
 
-            // Configure FluidSynth for high quality audio
-            fluid_settings_setnum(settings, CString::new("synth.sample-rate").unwrap().as_ptr(), 44100.0);
-            fluid_settings_setint(settings, CString::new("synth.audio-channels").unwrap().as_ptr(), 2);
-            fluid_settings_setint(settings, CString::new("synth.audio-groups").unwrap().as_ptr(), 2);
-            fluid_settings_setnum(settings, CString::new("synth.gain").unwrap().as_ptr(), 1.0);
-            fluid_settings_setint(settings, CString::new("synth.polyphony").unwrap().as_ptr(), 256);
-            // Enable reverb and chorus with proper integer settings
-            fluid_settings_setint(settings, CString::new("synth.reverb.active").unwrap().as_ptr(), 1);
-            fluid_settings_setint(settings, CString::new("synth.chorus.active").unwrap().as_ptr(), 1);
+        let mut settings = FluidSettings::new()?;
 
-            let synth = new_fluid_synth(settings);
-            if synth.is_null() {
-                delete_fluid_settings(settings);
-                return Err("Failed to create FluidSynth".to_string());
-            }
+        // A rejected setting (e.g. a misspelled name, or one this FluidSynth
+        // version doesn't know) would otherwise silently keep its default
+        let rejected = |e: ConversionError| ConversionError::FluidSynthInit(e.to_string());
+
+        // Configure FluidSynth for high quality audio
+        settings.set_num("synth.sample-rate", sample_rate as f64).map_err(rejected)?;
+        settings.set_int("synth.audio-channels", 2).map_err(rejected)?;
+        settings.set_int("synth.audio-groups", 2).map_err(rejected)?;
+        settings.set_num("synth.gain", DEFAULT_GAIN).map_err(rejected)?;
+        settings.set_int("synth.polyphony", DEFAULT_POLYPHONY as i32).map_err(rejected)?;
+        // Enable reverb and chorus with proper integer settings
+        settings.set_int("synth.reverb.active", 1).map_err(rejected)?;
+        settings.set_int("synth.chorus.active", 1).map_err(rejected)?;
 
-            Ok(MidiConverter {
-                settings,
-                synth,
-            })
+        let synth = unsafe { new_fluid_synth(settings.as_ptr()) };
+        if synth.is_null() {
+            return Err(ConversionError::FluidSynthInit("Failed to create FluidSynth".to_string()));
         }
+
+        Ok(MidiConverter {
+            settings,
+            synth,
+            sample_rate,
+            soundfonts: Vec::new(),
+            tempo_scale: 1.0,
+            muted_channels: 0,
+            transpose: 0,
+            metronome: None,
+            release_tail: ReleaseTail::default(),
+            buffer_size: BUFFER_SIZE,
+            warnings: Vec::new(),
+        })
     }
 
     /// Loads a SoundFont (.sf2) file for synthesis
@@ -78,122 +341,1368 @@ impl MidiConverter {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn load_soundfont(&mut self, sf2_path: &str) -> Result<(), String> {
-        unsafe {
-            let sf2_cstring = CString::new(sf2_path).map_err(|_| "Invalid SF2 path")?;
-            let sfont_id = fluid_synth_sfload(self.synth, sf2_cstring.as_ptr(), 1);
-            if sfont_id == -1 {
-                return Err("Failed to load soundfont".to_string());
-            }
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn load_soundfont(&mut self, sf2_path: &str) -> Result<(), ConversionError> {
+        self.add_soundfont(sf2_path, true)?;
+        Ok(())
+    }
+
+    /// Loads an additional SoundFont on top of those already loaded
+    /// 
+    /// FluidSynth keeps SoundFonts in a stack: when resolving a bank/program,
+    /// the most recently loaded SoundFont that has the preset wins. Load a base
+    /// SoundFont first and layer more specific ones (e.g. drums) after it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sf2_path` - Path to the SoundFont file (.sf2)
+    /// * `reset_presets` - `true` to re-select the presets of all channels afterwards,
+    ///   so the new SoundFont takes effect for programs already chosen
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(sfont_id)` on success, or `Err(ConversionError)` with error message.
+    /// A path containing a NUL byte is rejected with `InvalidArgument`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::error::ConversionError;
+    /// use yks_converter_example::midi_converter::MidiConverter;
+    /// 
+    /// let mut converter = MidiConverter::new()?;
+    /// let result = converter.add_soundfont("a\0b.sf2", true);
+    /// assert!(matches!(result, Err(ConversionError::InvalidArgument(_))));
+    /// assert!(!converter.soundfont_loaded());
+    /// # Ok::<(), ConversionError>(())
+    /// ```
+    pub fn add_soundfont(&mut self, sf2_path: &str, reset_presets: bool) -> Result<i32, ConversionError> {
+        // Bound to a local so the pointer passed below outlives the call (see fluid_settings)
+        let sf2_cstring = CString::new(sf2_path)
+            .map_err(|_| ConversionError::nul_byte("SoundFont path", sf2_path))?;
+        let sfont_id = unsafe { fluid_synth_sfload(self.synth, sf2_cstring.as_ptr(), reset_presets as i32) };
+        if sfont_id == -1 {
+            return Err(ConversionError::SoundFontLoad("Failed to load soundfont".to_string()));
+        }
+        self.soundfonts.push(sfont_id);
+        Ok(sfont_id)
+    }
+
+    /// Unloads a SoundFont previously loaded by [`MidiConverter::add_soundfont`]
+    /// 
+    /// Channel presets are re-selected so nothing keeps using the removed SoundFont.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sfont_id` - ID returned when the SoundFont was loaded
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if no such SoundFont is loaded.
+    pub fn remove_soundfont(&mut self, sfont_id: i32) -> Result<(), ConversionError> {
+        let Some(index) = self.soundfonts.iter().position(|&id| id == sfont_id) else {
+            return Err(ConversionError::InvalidArgument(format!("No soundfont loaded with id {}", sfont_id)));
+        };
+        if unsafe { fluid_synth_sfunload(self.synth, sfont_id, 1) } != 0 {
+            return Err(ConversionError::SoundFontLoad(format!("Failed to unload soundfont {}", sfont_id)));
         }
+        self.soundfonts.remove(index);
         Ok(())
     }
 
+    /// Returns the IDs of the loaded SoundFonts in load order
+    pub fn soundfont_ids(&self) -> &[i32] {
+        &self.soundfonts
+    }
+
+    /// Returns whether at least one SoundFont is loaded
+    /// 
+    /// Without a SoundFont FluidSynth has no presets and renders pure silence,
+    /// so every conversion method fails early instead.
+    pub fn soundfont_loaded(&self) -> bool {
+        !self.soundfonts.is_empty()
+    }
+
+    /// Lists the presets of all loaded SoundFonts
+    /// 
+    /// SoundFonts are listed in load order, each in the order FluidSynth
+    /// iterates its presets (sorted by bank, then program). When SoundFonts
+    /// are layered, a bank/program may appear more than once; the entry from
+    /// the most recently loaded SoundFont is the one that plays.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<PresetInfo>)` on success, or `Err(ConversionError)` if no SoundFont is loaded.
+    pub fn list_presets(&self) -> Result<Vec<PresetInfo>, ConversionError> {
+        self.require_soundfont()?;
+
+        let mut presets = Vec::new();
+        for &sfont_id in &self.soundfonts {
+            unsafe {
+                let sfont = fluid_synth_get_sfont_by_id(self.synth, sfont_id);
+                if sfont.is_null() {
+                    return Err(ConversionError::SoundFontLoad(format!("Soundfont {} is no longer loaded", sfont_id)));
+                }
+
+                fluid_sfont_iteration_start(sfont);
+                loop {
+                    let preset = fluid_sfont_iteration_next(sfont);
+                    if preset.is_null() {
+                        break;
+                    }
+                    let name = fluid_preset_get_name(preset);
+                    let name = if name.is_null() {
+                        String::new()
+                    } else {
+                        CStr::from_ptr(name).to_string_lossy().into_owned()
+                    };
+                    presets.push(PresetInfo {
+                        bank: fluid_preset_get_banknum(preset) as u16,
+                        program: fluid_preset_get_num(preset) as u8,
+                        name,
+                    });
+                }
+            }
+        }
+        Ok(presets)
+    }
+
+    /// Fails with a hint to load a SoundFont if none is loaded
+    pub(crate) fn require_soundfont(&self) -> Result<(), ConversionError> {
+        if self.soundfont_loaded() {
+            Ok(())
+        } else {
+            Err(ConversionError::SoundFontLoad("No SoundFont loaded; call load_soundfont first".to_string()))
+        }
+    }
+
     /// Sets the instrument for MIDI channel 0
     /// 
+    /// See [`MidiConverter::set_instrument_on_channel`] for when the MIDI data
+    /// overrides it.
+    /// 
     /// # Arguments
     /// 
     /// * `program` - MIDI program number (0-127)
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn set_instrument(&mut self, program: u8) -> Result<(), String> {
-        unsafe {
-            let result = fluid_synth_program_change(self.synth, 0, program as i32);
-            if result != 0 {
-                return Err(format!("Failed to change instrument to program {}", program));
-            }
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_instrument(&mut self, program: u8) -> Result<(), ConversionError> {
+        self.set_instrument_on_channel(0, program)
+    }
+
+    /// Sets the instrument for a MIDI channel
+    /// 
+    /// The program stays selected across renders, until it is changed again.
+    /// Program change events in the rendered MIDI take precedence: when the
+    /// player reaches one for this channel, it replaces the instrument set
+    /// here, for the rest of that render and the following ones. MIDI generated
+    /// from MML starts every part with a program change, so for MML choose the
+    /// instrument with [`MmlConverter::set_instrument`](crate::mml_converter::MmlConverter::set_instrument)
+    /// instead; this method only decides the sound of channels the MIDI never
+    /// assigns a program to.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chan` - MIDI channel (0-15)
+    /// * `program` - MIDI program number (0-127)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_instrument_on_channel(&mut self, chan: u8, program: u8) -> Result<(), ConversionError> {
+        channel_bit(chan)?;
+        if program > 127 {
+            return Err(ConversionError::InvalidArgument(format!("Invalid program {} (expected 0-127)", program)));
+        }
+
+        if unsafe { fluid_synth_program_change(self.synth, chan as i32, program as i32) } != 0 {
+            return Err(ConversionError::Synthesis(format!(
+                "Failed to change instrument on channel {} to program {}",
+                chan, program
+            )));
         }
         Ok(())
     }
 
-    /// Converts a MIDI file to WAV format using FluidSynth synthesis
+    /// Sets the master gain of the synthesizer
+    /// 
+    /// `synth.gain` is a real-time setting, so it is applied to the live synth
+    /// and affects every conversion from then on. Lower it if loud multi-voice
+    /// MML clips; raise it for quiet SoundFonts.
     /// 
     /// # Arguments
     /// 
-    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
-    /// * `wav_path` - Path for the output WAV file
+    /// * `gain` - Gain factor (0.0-10.0, default [`DEFAULT_GAIN`])
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the gain is out of range.
+    pub fn set_gain(&mut self, gain: f64) -> Result<(), ConversionError> {
+        if !(0.0..=10.0).contains(&gain) {
+            return Err(ConversionError::InvalidArgument(format!("Gain must be between 0.0 and 10.0, got {}", gain)));
+        }
+        self.settings.set_num("synth.gain", gain)
+    }
+
+    /// Sets how many voices the synthesizer can play at once
     /// 
-    /// # Quality Settings
+    /// When dense multi-voice music needs more voices than this, FluidSynth
+    /// steals the oldest ones and notes cut off early. Each voice costs CPU
+    /// time, so orchestral SoundFonts with layered samples benefit from 512 or
+    /// 1024, while going far beyond that mostly slows rendering down.
     /// 
-    /// - 44.1 kHz sample rate
-    /// - 16-bit stereo output
-    /// - 4096 sample buffer for optimal quality
-    pub fn convert_midi_to_wav(&mut self, midi_path: &str, wav_path: &str) -> Result<(), String> {
-        unsafe {
-            let spec = WavSpec {
-                channels: 2,
-                sample_rate: 44100,
-                bits_per_sample: 16,
-                sample_format: hound::SampleFormat::Int,
-            };
-
-            let mut writer = WavWriter::create(wav_path, spec)
-                .map_err(|e| format!("Failed to create WAV writer: {}", e))?;
-
-            let player = new_fluid_player(self.synth);
-            if player.is_null() {
-                return Err("Failed to create MIDI player".to_string());
-            }
-
-            let midi_cstring = CString::new(midi_path).map_err(|_| "Invalid MIDI path")?;
-            if fluid_player_add(player, midi_cstring.as_ptr()) != 0 {
-                delete_fluid_player(player);
-                return Err("Failed to add MIDI file to player".to_string());
-            }
-
-            fluid_player_play(player);
-
-            const BUFFER_SIZE: usize = 4096; // Larger buffer for better quality
-            let mut left_buffer = vec![0i16; BUFFER_SIZE];
-            let mut right_buffer = vec![0i16; BUFFER_SIZE];
-
-            while fluid_player_get_status(player) == FLUID_PLAYER_PLAYING as i32 {
-                let result = fluid_synth_write_s16(
-                    self.synth,
-                    BUFFER_SIZE as i32,
-                    left_buffer.as_mut_ptr(),
-                    0,
-                    1,
-                    right_buffer.as_mut_ptr(),
-                    0,
-                    1,
-                );
-
-                if result != 0 {
-                    break;
-                }
+    /// # Arguments
+    /// 
+    /// * `voices` - Maximum simultaneous voices (1-65535, default [`DEFAULT_POLYPHONY`])
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the value is out of range.
+    pub fn set_polyphony(&mut self, voices: u32) -> Result<(), ConversionError> {
+        if !(1..=MAX_POLYPHONY).contains(&voices) {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Polyphony must be between 1 and {}, got {}",
+                MAX_POLYPHONY, voices
+            )));
+        }
+        if unsafe { fluid_synth_set_polyphony(self.synth, voices as i32) } != 0 {
+            return Err(ConversionError::Synthesis(format!("FluidSynth rejected polyphony {}", voices)));
+        }
+        Ok(())
+    }
 
-                for i in 0..BUFFER_SIZE {
-                    writer.write_sample(left_buffer[i])
-                        .map_err(|e| format!("Failed to write left sample: {}", e))?;
-                    writer.write_sample(right_buffer[i])
-                        .map_err(|e| format!("Failed to write right sample: {}", e))?;
-                }
-            }
+    /// Plays MIDI files faster or slower without changing pitch
+    /// 
+    /// The scale is handed to FluidSynth's player (`fluid_player_set_tempo`)
+    /// as a multiplier of the file's own tempo, so tempo changes in the middle
+    /// of a song still take effect, scaled by the same factor. The MIDI data is
+    /// not modified. For MML, [`MmlConverter::set_tempo_scale`](crate::mml_converter::MmlConverter::set_tempo_scale)
+    /// does the same by rewriting the generated MIDI; set only one of the two,
+    /// or the factors multiply.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - Speed factor from 0.25 to 4.0; 1.0 plays at the original tempo
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the scale is out of range.
+    pub fn set_tempo_scale(&mut self, scale: f64) -> Result<(), ConversionError> {
+        midi_file::validate_tempo_scale(scale)?;
+        self.tempo_scale = scale;
+        Ok(())
+    }
 
-            delete_fluid_player(player);
-            writer.finalize().map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+    /// Sets how many frames are synthesized per buffer
+    /// 
+    /// Smaller buffers lower latency: progress callbacks, cancellation and
+    /// [`MidiConverter::synth_frames`] react after every buffer, so a 512-frame
+    /// buffer suits live previews. Larger buffers cut the per-buffer overhead
+    /// of FFI calls and callbacks, which helps batch throughput. The audio is
+    /// the same either way. FluidSynth renders in blocks of 64 frames, and
+    /// sizes that are not a power of two are accepted with a warning queued
+    /// for [`MidiConverter::take_warnings`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `frames` - Frames per buffer (1 to [`MAX_BUFFER_SIZE`]), [`BUFFER_SIZE`] by default
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the size is out of range.
+    pub fn set_buffer_size(&mut self, frames: usize) -> Result<(), ConversionError> {
+        if !(1..=MAX_BUFFER_SIZE).contains(&frames) {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Buffer size must be between 1 and {} frames, got {}",
+                MAX_BUFFER_SIZE, frames
+            )));
         }
+        if !frames.is_power_of_two() {
+            self.warnings.push(format!("buffer size {} is not a power of two", frames));
+        }
+        self.buffer_size = frames;
         Ok(())
     }
-}
 
-impl Drop for MidiConverter {
-    fn drop(&mut self) {
-        unsafe {
-            if !self.synth.is_null() {
-                delete_fluid_synth(self.synth);
-            }
-            if !self.settings.is_null() {
-                delete_fluid_settings(self.settings);
-            }
+    /// Returns the number of frames synthesized per buffer
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Returns and clears the warnings collected since the last call
+    /// 
+    /// The converter never prints; settings and renders that hit something
+    /// worth reporting, such as notes clamped by [`MidiConverter::set_transpose`],
+    /// queue a warning here instead.
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Keeps synthesizing for a while after the last MIDI event
+    /// 
+    /// The player stops as soon as its last event has been sent, which cuts off
+    /// decaying reverb and releasing notes. A tail renders that decay too, and
+    /// with `fade` set it is faded out so the render always ends in silence.
+    /// No tail is added when synthesis is stopped early, e.g. for a preview,
+    /// or by [`MidiConverter::synth_frames`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tail` - Tail length (at most [`MAX_TAIL_MS`]) and whether to fade it
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the tail is too long.
+    pub fn set_release_tail(&mut self, tail: ReleaseTail) -> Result<(), ConversionError> {
+        if tail.tail_ms > MAX_TAIL_MS {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Release tail must be at most {} ms, got {}",
+                MAX_TAIL_MS, tail.tail_ms
+            )));
+        }
+        self.release_tail = tail;
+        Ok(())
+    }
+
+    /// Mutes or unmutes a MIDI channel for the following renders
+    /// 
+    /// Note-on events on muted channels are removed from the MIDI data before
+    /// it reaches the player; everything else, including controllers and
+    /// program changes, still plays, so unmuting restores the exact mix.
+    /// Setting the channel volume (CC 7) to zero instead would be undone by
+    /// the first volume event in the file. In General MIDI, channel 9 (the
+    /// tenth channel, counting from 1) is the drum channel, so muting it
+    /// removes the percussion.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chan` - MIDI channel (0-15)
+    /// * `enabled` - `false` to mute the channel, `true` to play it again
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the channel is out of range.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::midi_converter::MidiConverter;
+    /// 
+    /// let mut converter = MidiConverter::new()?;
+    /// converter.load_soundfont("soundfont.sf2")?;
+    /// // Render without the General MIDI drum channel
+    /// converter.set_channel_enabled(9, false)?;
+    /// converter.convert_midi_to_wav("song.mid", "no_drums.wav")?;
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn set_channel_enabled(&mut self, chan: u8, enabled: bool) -> Result<(), ConversionError> {
+        let bit = channel_bit(chan)?;
+        if enabled {
+            self.muted_channels &= !bit;
+        } else {
+            self.muted_channels |= bit;
+        }
+        Ok(())
+    }
+
+    /// Mutes every channel except `chan`
+    /// 
+    /// See [`MidiConverter::set_channel_enabled`]; call
+    /// [`MidiConverter::enable_all_channels`] to hear the full mix again.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chan` - MIDI channel (0-15) to keep
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the channel is out of range.
+    pub fn solo_channel(&mut self, chan: u8) -> Result<(), ConversionError> {
+        self.muted_channels = !channel_bit(chan)?;
+        Ok(())
+    }
+
+    /// Unmutes all channels
+    pub fn enable_all_channels(&mut self) {
+        self.muted_channels = 0;
+    }
+
+    /// Transposes MIDI input by a number of semitones before playback
+    /// 
+    /// The MIDI data is rewritten before it reaches the player, see
+    /// [`MidiFile::transpose`]: the drum channel is left alone, notes pushed
+    /// outside the MIDI range are clamped, and a warning with the number of
+    /// clamped notes is queued for [`MidiConverter::take_warnings`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `semitones` - Interval to shift by, e.g. 3 to go up a minor third; 0 disables transposition
+    pub fn set_transpose(&mut self, semitones: i8) {
+        self.transpose = semitones;
+    }
+
+    /// Adds a metronome click on every beat of MIDI input
+    /// 
+    /// The clicks are added as percussion notes on the drum channel before
+    /// the MIDI data reaches the player, see [`MidiFile::add_click_track`].
+    /// Beats follow the file's time signatures, so tempo changes and
+    /// [`MidiConverter::set_tempo_scale`] carry the click along with the
+    /// music. The first beat of each bar is accented with its own sound and
+    /// a higher velocity. Muting the drum channel does not mute the clicks.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - `true` to add clicks
+    /// * `accent_program` - General MIDI percussion key (0-127) for the downbeat,
+    ///   e.g. [`midi_file::METRONOME_ACCENT_NOTE`]; other beats use [`midi_file::METRONOME_CLICK_NOTE`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the key is out of range.
+    pub fn set_metronome(&mut self, enabled: bool, accent_program: u8) -> Result<(), ConversionError> {
+        if accent_program > 127 {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Metronome accent must be a percussion key between 0 and 127, got {}",
+                accent_program
+            )));
         }
+        self.metronome = enabled.then_some(accent_program);
+        Ok(())
+    }
+
+    /// Returns `true` unless `chan` is muted
+    pub fn is_channel_enabled(&self, chan: u8) -> bool {
+        chan > 15 || self.muted_channels & (1 << chan) == 0
     }
-}
\ No newline at end of file
+
+    /// Selects a bank and program on a MIDI channel
+    /// 
+    /// Program change alone only reaches bank 0; SoundFonts often keep drum kits
+    /// and instrument variations (GS/GM2) on other banks.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chan` - MIDI channel (0-15)
+    /// * `bank` - Bank number (0-16383)
+    /// * `program` - MIDI program number (0-127)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_bank_and_program(&mut self, chan: u8, bank: u16, program: u8) -> Result<(), ConversionError> {
+        if chan > 15 || bank > 16383 || program > 127 {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Invalid channel {}, bank {} or program {} (expected 0-15, 0-16383, 0-127)",
+                chan, bank, program
+            )));
+        }
+
+        unsafe {
+            if fluid_synth_bank_select(self.synth, chan as i32, bank as i32) != 0 {
+                return Err(ConversionError::Synthesis(format!("Failed to select bank {} on channel {}", bank, chan)));
+            }
+            if fluid_synth_program_change(self.synth, chan as i32, program as i32) != 0 {
+                return Err(ConversionError::Synthesis(format!("Failed to change instrument to bank {} program {}", bank, program)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Configures the reverb effect and enables it
+    /// 
+    /// The values are written to the `synth.reverb.*` settings of the live synth's
+    /// settings object. FluidSynth 2.x registers these settings as real-time, so
+    /// the running synthesizer picks up the change immediately; no re-creation
+    /// is needed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `roomsize` - Room size (0.0-1.0)
+    /// * `damping` - High-frequency damping (0.0-1.0)
+    /// * `width` - Stereo spread (0.0-100.0)
+    /// * `level` - Output level (0.0-1.0)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if a value is out of range.
+    pub fn set_reverb(&mut self, roomsize: f64, damping: f64, width: f64, level: f64) -> Result<(), ConversionError> {
+        for (name, value, max) in [
+            ("synth.reverb.room-size", roomsize, 1.0),
+            ("synth.reverb.damp", damping, 1.0),
+            ("synth.reverb.width", width, 100.0),
+            ("synth.reverb.level", level, 1.0),
+        ] {
+            if !(0.0..=max).contains(&value) {
+                return Err(ConversionError::InvalidArgument(format!("{} must be between 0 and {}, got {}", name, max, value)));
+            }
+        }
+
+        self.settings.set_num("synth.reverb.room-size", roomsize)?;
+        self.settings.set_num("synth.reverb.damp", damping)?;
+        self.settings.set_num("synth.reverb.width", width)?;
+        self.settings.set_num("synth.reverb.level", level)?;
+        self.settings.set_int("synth.reverb.active", 1)
+    }
+
+    /// Turns the reverb effect off
+    /// 
+    /// Like [`MidiConverter::set_reverb`], this updates the real-time
+    /// `synth.reverb.active` setting and applies to the live synth.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn disable_reverb(&mut self) -> Result<(), ConversionError> {
+        self.settings.set_int("synth.reverb.active", 0)
+    }
+
+    /// Configures the chorus effect and enables it
+    /// 
+    /// As with reverb, the `synth.chorus.*` values are real-time settings and are
+    /// applied to the live synth. FluidSynth has no setting for the modulation
+    /// waveform, so `type_` goes through `fluid_synth_set_chorus_type` instead.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `nr` - Number of chorus voices (0-99)
+    /// * `level` - Output level (0.0-10.0)
+    /// * `speed` - Modulation speed in Hz (0.1-5.0)
+    /// * `depth` - Modulation depth in milliseconds (0.0-256.0)
+    /// * `type_` - [`FLUID_CHORUS_MOD_SINE`] or [`FLUID_CHORUS_MOD_TRIANGLE`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if a value is out of range.
+    pub fn set_chorus(&mut self, nr: i32, level: f64, speed: f64, depth: f64, type_: i32) -> Result<(), ConversionError> {
+        if !(0..=99).contains(&nr) {
+            return Err(ConversionError::InvalidArgument(format!("synth.chorus.nr must be between 0 and 99, got {}", nr)));
+        }
+        for (name, value, min, max) in [
+            ("synth.chorus.level", level, 0.0, 10.0),
+            ("synth.chorus.speed", speed, 0.1, 5.0),
+            ("synth.chorus.depth", depth, 0.0, 256.0),
+        ] {
+            if !(min..=max).contains(&value) {
+                return Err(ConversionError::InvalidArgument(format!("{} must be between {} and {}, got {}", name, min, max, value)));
+            }
+        }
+        if type_ != FLUID_CHORUS_MOD_SINE && type_ != FLUID_CHORUS_MOD_TRIANGLE {
+            return Err(ConversionError::InvalidArgument(format!("Unknown chorus type {}", type_)));
+        }
+
+        self.settings.set_int("synth.chorus.nr", nr)?;
+        self.settings.set_num("synth.chorus.level", level)?;
+        self.settings.set_num("synth.chorus.speed", speed)?;
+        self.settings.set_num("synth.chorus.depth", depth)?;
+        if unsafe { fluid_synth_set_chorus_type(self.synth, type_) } != 0 {
+            return Err(ConversionError::Synthesis(format!("Failed to set chorus type {}", type_)));
+        }
+        self.settings.set_int("synth.chorus.active", 1)
+    }
+
+    /// Turns the chorus effect off for a dry signal path
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn disable_chorus(&mut self) -> Result<(), ConversionError> {
+        self.settings.set_int("synth.chorus.active", 0)
+    }
+
+    /// Converts a MIDI file to WAV format using FluidSynth synthesis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `wav_path` - Path for the output WAV file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Quality Settings
+    /// 
+    /// - 44.1 kHz sample rate (see [`MidiConverter::with_sample_rate`])
+    /// - 16-bit stereo output
+    /// - 4096 frame buffer (see [`MidiConverter::set_buffer_size`])
+    pub fn convert_midi_to_wav(&mut self, midi_path: &str, wav_path: &str) -> Result<(), ConversionError> {
+        self.require_soundfont()?;
+        let file = File::create(wav_path)
+            .map_err(|e| ConversionError::io("Failed to create WAV file", e))?;
+        self.write_wav(midi_path, BufWriter::new(file))
+    }
+
+    /// Converts in-memory MIDI data to a WAV file
+    /// 
+    /// The data is handed to FluidSynth with `fluid_player_add_mem`, so MIDI
+    /// produced by [`MmlConverter::convert_mml_to_midi_bytes`](crate::mml_converter::MmlConverter::convert_mml_to_midi_bytes)
+    /// never has to be written to disk.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_data` - Standard MIDI file contents
+    /// * `wav_path` - Path for the output WAV file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// Without a loaded SoundFont it fails with `SoundFontLoad` before
+    /// creating the WAV file, rather than writing silence.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::error::ConversionError;
+    /// use yks_converter_example::midi_converter::MidiConverter;
+    /// 
+    /// let wav_path = std::env::temp_dir().join(format!("yks_no_soundfont_{}.wav", std::process::id()));
+    /// let wav_path = wav_path.to_str().unwrap();
+    /// let result = MidiConverter::new()?.convert_midi_bytes_to_wav(b"MThd", wav_path);
+    /// assert!(matches!(result, Err(ConversionError::SoundFontLoad(_))));
+    /// assert!(!std::path::Path::new(wav_path).exists());
+    /// # Ok::<(), ConversionError>(())
+    /// ```
+    pub fn convert_midi_bytes_to_wav(&mut self, midi_data: &[u8], wav_path: &str) -> Result<(), ConversionError> {
+        self.convert_midi_bytes_to_wav_reporting(midi_data, wav_path)?;
+        Ok(())
+    }
+
+    /// Converts in-memory MIDI data to a WAV file and reports clipping
+    /// 
+    /// See [`MidiConverter::convert_midi_to_wav_reporting`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_data` - Standard MIDI file contents
+    /// * `wav_path` - Path for the output WAV file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(SynthReport)` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_midi_bytes_to_wav_reporting(&mut self, midi_data: &[u8], wav_path: &str) -> Result<SynthReport, ConversionError> {
+        self.convert_midi_bytes_to_wav_until(midi_data, wav_path, StopConditions::default())
+    }
+
+    /// Like [`MidiConverter::convert_midi_bytes_to_wav_reporting`], but stops
+    /// early as described by `stop`
+    pub(crate) fn convert_midi_bytes_to_wav_until(
+        &mut self,
+        midi_data: &[u8],
+        wav_path: &str,
+        stop: StopConditions<'_>,
+    ) -> Result<SynthReport, ConversionError> {
+        self.require_soundfont()?;
+        let file = File::create(wav_path)
+            .map_err(|e| ConversionError::io("Failed to create WAV file", e))?;
+        self.render_wav(MidiSource::Bytes(midi_data), BufWriter::new(file), None, stop)
+    }
+
+    /// Converts a MIDI file to WAV and reports clipping in the synthesized audio
+    /// 
+    /// Useful for retrying with a lower [`MidiConverter::set_gain`] when the
+    /// report shows clipped samples.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `wav_path` - Path for the output WAV file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(SynthReport)` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_midi_to_wav_reporting(&mut self, midi_path: &str, wav_path: &str) -> Result<SynthReport, ConversionError> {
+        self.require_soundfont()?;
+        let file = File::create(wav_path)
+            .map_err(|e| ConversionError::io("Failed to create WAV file", e))?;
+        self.render_wav(MidiSource::File(midi_path), BufWriter::new(file), None, StopConditions::default())
+    }
+
+    /// Converts a MIDI file to WAV with a linear fade-in and fade-out
+    /// 
+    /// The whole rendering is buffered in memory so the fade-out can be placed
+    /// at the end. Both channels receive the same per-frame gain. With both
+    /// lengths set to zero the output is identical to
+    /// [`MidiConverter::convert_midi_to_wav`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `wav_path` - Path for the output WAV file
+    /// * `fade_in_ms` - Fade-in length in milliseconds
+    /// * `fade_out_ms` - Fade-out length in milliseconds
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_midi_to_wav_with_fades(
+        &mut self,
+        midi_path: &str,
+        wav_path: &str,
+        fade_in_ms: u32,
+        fade_out_ms: u32,
+    ) -> Result<(), ConversionError> {
+        if fade_in_ms == 0 && fade_out_ms == 0 {
+            return self.convert_midi_to_wav(midi_path, wav_path);
+        }
+
+        let mut samples = Vec::new();
+        self.synthesize(MidiSource::File(midi_path), None, StopConditions::default(), |left, right| {
+            for (&l, &r) in left.iter().zip(right) {
+                samples.push(l);
+                samples.push(r);
+            }
+            Ok(())
+        })?;
+
+        let fade_in_frames = (fade_in_ms as u64 * self.sample_rate as u64 / 1000) as usize;
+        let fade_out_frames = (fade_out_ms as u64 * self.sample_rate as u64 / 1000) as usize;
+        apply_fades(&mut samples, fade_in_frames, fade_out_frames);
+
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(wav_path, spec)
+            .map_err(|e| ConversionError::wav("Failed to create WAV writer", e))?;
+        for sample in samples {
+            writer.write_sample(sample)
+                .map_err(|e| ConversionError::wav("Failed to write sample", e))?;
+        }
+        writer.finalize().map_err(|e| ConversionError::wav("Failed to finalize WAV", e))?;
+
+        Ok(())
+    }
+
+    /// Synthesizes a MIDI file and streams the WAV data into `writer`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `writer` - Destination for the WAV data, e.g. a `Cursor<Vec<u8>>` or a file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use std::io::Cursor;
+    /// use yks_converter_example::midi_converter::MidiConverter;
+    /// 
+    /// let mut converter = MidiConverter::new()?;
+    /// converter.load_soundfont("soundfont.sf2")?;
+    /// let mut wav_data = Cursor::new(Vec::new());
+    /// converter.write_wav("input.mid", &mut wav_data)?;
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn write_wav<W: Write + Seek>(&mut self, midi_path: &str, writer: W) -> Result<(), ConversionError> {
+        self.render_wav(MidiSource::File(midi_path), writer, None, StopConditions::default())?;
+        Ok(())
+    }
+
+    /// Converts a MIDI file to WAV, reporting progress while synthesizing
+    /// 
+    /// `progress` is called after every synthesized buffer with the fraction
+    /// (0.0–1.0) of MIDI ticks played so far. Some MIDI files do not report a
+    /// total tick count to FluidSynth; for those the fraction stays at 0.0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `wav_path` - Path for the output WAV file
+    /// * `progress` - Callback receiving the completed fraction
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_midi_to_wav_with_progress(
+        &mut self,
+        midi_path: &str,
+        wav_path: &str,
+        mut progress: impl FnMut(f64),
+    ) -> Result<(), ConversionError> {
+        self.require_soundfont()?;
+        let file = File::create(wav_path)
+            .map_err(|e| ConversionError::io("Failed to create WAV file", e))?;
+        self.render_wav(MidiSource::File(midi_path), BufWriter::new(file), Some(&mut progress), StopConditions::default())?;
+        Ok(())
+    }
+
+    /// Converts a MIDI file to WAV, stopping early once `cancel` is set
+    /// 
+    /// The flag is checked before every synthesized buffer, so cancellation takes
+    /// effect within one buffer (by default 4096 frames, about 93 ms of audio
+    /// at 44.1 kHz, usually much less wall-clock time). On cancellation the player is
+    /// released and the partial WAV file is removed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `wav_path` - Path for the output WAV file
+    /// * `cancel` - Flag another thread sets to abort the conversion
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, `Err(ConversionError::Cancelled)` if cancelled,
+    /// or another `Err(ConversionError)` on failure.
+    pub fn convert_midi_to_wav_cancellable(
+        &mut self,
+        midi_path: &str,
+        wav_path: &str,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<(), ConversionError> {
+        self.require_soundfont()?;
+        let file = File::create(wav_path)
+            .map_err(|e| ConversionError::io("Failed to create WAV file", e))?;
+        let stop = StopConditions { cancel: Some(&cancel), ..StopConditions::default() };
+        let result = self.render_wav(MidiSource::File(midi_path), BufWriter::new(file), None, stop);
+        if let Err(ConversionError::Cancelled) = result {
+            let _ = std::fs::remove_file(wav_path);
+        }
+        result.map(|_| ())
+    }
+
+    /// Converts a MIDI file to a 24-bit WAV file
+    /// 
+    /// FluidSynth renders floating point samples, which are scaled to 24-bit
+    /// PCM instead of 16-bit. This avoids audible quantization noise on quiet
+    /// passages such as reverb tails, which matters when the WAV is kept or
+    /// edited further.
+    /// 
+    /// [`Mp3Encoder`](crate::mp3_encoder::Mp3Encoder) reads 24-bit WAV directly,
+    /// so no 16-bit intermediate is needed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the input MIDI file (.mid, .midi)
+    /// * `wav_path` - Path for the output WAV file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_midi_to_wav_24bit(&mut self, midi_path: &str, wav_path: &str) -> Result<(), ConversionError> {
+        self.require_soundfont()?;
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut writer = WavWriter::create(wav_path, spec)
+            .map_err(|e| ConversionError::wav("Failed to create WAV writer", e))?;
+
+        self.synthesize(MidiSource::File(midi_path), None, StopConditions::default(), |left: &[f32], right: &[f32]| {
+            for (&l, &r) in left.iter().zip(right) {
+                writer.write_sample(float_to_i24(l))
+                    .map_err(|e| ConversionError::wav("Failed to write left sample", e))?;
+                writer.write_sample(float_to_i24(r))
+                    .map_err(|e| ConversionError::wav("Failed to write right sample", e))?;
+            }
+            Ok(())
+        })?;
+
+        writer.finalize().map_err(|e| ConversionError::wav("Failed to finalize WAV", e))?;
+        Ok(())
+    }
+
+    /// Synthesizes `source` and writes 16-bit stereo WAV data into `writer`
+    fn render_wav<W: Write + Seek>(
+        &mut self,
+        source: MidiSource<'_>,
+        writer: W,
+        progress: Option<&mut dyn FnMut(f64)>,
+        stop: StopConditions<'_>,
+    ) -> Result<SynthReport, ConversionError> {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut writer = WavWriter::new(writer, spec)
+            .map_err(|e| ConversionError::wav("Failed to create WAV writer", e))?;
+
+        let mut report = SynthReport::default();
+        self.synthesize(source, progress, stop, |left, right| {
+            for (&l, &r) in left.iter().zip(right) {
+                report.record(l);
+                report.record(r);
+                writer.write_sample(l)
+                    .map_err(|e| ConversionError::wav("Failed to write left sample", e))?;
+                writer.write_sample(r)
+                    .map_err(|e| ConversionError::wav("Failed to write right sample", e))?;
+            }
+            Ok(())
+        })?;
+
+        writer.finalize().map_err(|e| ConversionError::wav("Failed to finalize WAV", e))?;
+        Ok(report)
+    }
+
+    /// Synthesizes in-memory MIDI data into interleaved stereo samples
+    /// 
+    /// Nothing is written to the filesystem, which makes this suitable for
+    /// read-only environments and concurrent conversions.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_data` - Standard MIDI file contents
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<i16>)` with interleaved left/right samples at
+    /// [`MidiConverter::sample_rate`], or `Err(ConversionError)` with error message.
+    pub fn render_midi_bytes(&mut self, midi_data: &[u8]) -> Result<Vec<i16>, ConversionError> {
+        self.render_interleaved(MidiSource::Bytes(midi_data))
+    }
+
+    /// Synthesizes a MIDI file into interleaved stereo samples
+    /// 
+    /// The result can go straight to [`Mp3Encoder::encode_interleaved`](crate::mp3_encoder::Mp3Encoder::encode_interleaved)
+    /// without a WAV file in between.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the MIDI file (.mid, .midi)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<i16>)` with interleaved left/right samples at
+    /// [`MidiConverter::sample_rate`], or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::midi_converter::MidiConverter;
+    /// use yks_converter_example::mp3_encoder::{Mp3Encoder, Mp3EncoderConfig};
+    /// 
+    /// let mut converter = MidiConverter::new()?;
+    /// converter.load_soundfont("soundfont.sf2")?;
+    /// let pcm = converter.convert_midi_to_interleaved("input.mid")?;
+    /// let mp3_data = Mp3Encoder::encode_interleaved(&pcm, converter.sample_rate(), &Mp3EncoderConfig::default())?;
+    /// std::fs::write("output.mp3", mp3_data).unwrap();
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_midi_to_interleaved(&mut self, midi_path: &str) -> Result<Vec<i16>, ConversionError> {
+        self.render_interleaved(MidiSource::File(midi_path))
+    }
+
+    /// Synthesizes `source` into interleaved stereo samples
+    fn render_interleaved(&mut self, source: MidiSource<'_>) -> Result<Vec<i16>, ConversionError> {
+        let mut samples = Vec::new();
+        self.synthesize(source, None, StopConditions::default(), |left, right| {
+            for (&l, &r) in left.iter().zip(right) {
+                samples.push(l);
+                samples.push(r);
+            }
+            Ok(())
+        })?;
+        Ok(samples)
+    }
+
+    /// Estimates the playback length of a MIDI file without synthesizing it
+    /// 
+    /// The length is computed from the file's tempo map and the tick of its
+    /// last event, adjusted by [`MidiConverter::set_tempo_scale`], so it does
+    /// not include reverb tails.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the MIDI file (.mid, .midi)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(seconds)` on success, or `Err(ConversionError)` with error message.
+    pub fn estimate_duration_secs(&self, midi_path: &str) -> Result<f64, ConversionError> {
+        let midi_data = std::fs::read(midi_path)
+            .map_err(|e| ConversionError::io("Failed to read MIDI file", e))?;
+        Ok(MidiFile::parse(&midi_data)?.duration_secs() / self.tempo_scale)
+    }
+
+    /// Starts synthesizing a MIDI file one buffer at a time
+    /// 
+    /// Unlike the `convert_*` methods, nothing is rendered up front: every
+    /// `next()` on the returned iterator synthesizes the following
+    /// [`MidiConverter::buffer_size`] frames, so a preview or live waveform can consume
+    /// audio as it is produced and stop early by dropping the iterator.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_path` - Path to the MIDI file (.mid, .midi)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(SynthFrames)` once the player has started, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::midi_converter::MidiConverter;
+    /// 
+    /// let mut converter = MidiConverter::new()?;
+    /// converter.load_soundfont("soundfont.sf2")?;
+    /// for frames in converter.synth_frames("input.mid")? {
+    ///     let (left, right) = frames?;
+    ///     let peak = left.iter().chain(&right).map(|sample| sample.unsigned_abs()).max().unwrap_or(0);
+    ///     println!("{}", "#".repeat(peak as usize * 60 / 32768));
+    /// }
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn synth_frames(&mut self, midi_path: &str) -> Result<SynthFrames<'_>, ConversionError> {
+        let player = self.start_player(MidiSource::File(midi_path))?;
+        Ok(SynthFrames { converter: self, player, finished: false })
+    }
+
+    /// Returns the sample rate of the synthesized audio in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Plays MIDI data through the synth, handing each rendered buffer to `on_buffer`
+    /// 
+    /// If given, `progress` receives the fraction of ticks played after each
+    /// buffer. Synthesis ends early as described by `stop`.
+    /// Whichever way it ends, including errors reported by `on_buffer`, the
+    /// player is deleted and every voice is silenced, so nothing carries over
+    /// into the next conversion.
+    fn synthesize<S, F>(
+        &mut self,
+        source: MidiSource<'_>,
+        progress: Option<&mut dyn FnMut(f64)>,
+        stop: StopConditions<'_>,
+        on_buffer: F,
+    ) -> Result<(), ConversionError>
+    where
+        S: SynthSample,
+        F: FnMut(&[S], &[S]) -> Result<(), ConversionError>,
+    {
+        let player = self.start_player(source)?;
+        let result = self.run_player(&player, progress, stop, on_buffer);
+        drop(player);
+        self.silence();
+        result
+    }
+
+    /// Renders buffers while `player` is playing, see [`MidiConverter::synthesize`]
+    fn run_player<S, F>(
+        &mut self,
+        player: &FluidPlayer,
+        mut progress: Option<&mut dyn FnMut(f64)>,
+        stop: StopConditions<'_>,
+        mut on_buffer: F,
+    ) -> Result<(), ConversionError>
+    where
+        S: SynthSample,
+        F: FnMut(&[S], &[S]) -> Result<(), ConversionError>,
+    {
+        let buffer_size = self.buffer_size;
+        let mut left_buffer = vec![S::default(); buffer_size];
+        let mut right_buffer = vec![S::default(); buffer_size];
+        let mut remaining_frames = stop.max_frames;
+
+        while player.is_playing() && remaining_frames != Some(0) {
+            stop.check()?;
+
+            let status = unsafe { S::write(self.synth, buffer_size, &mut left_buffer, &mut right_buffer) };
+
+            if status != 0 {
+                break;
+            }
+
+            let frames = remaining_frames.map_or(buffer_size, |remaining| remaining.min(buffer_size));
+            on_buffer(&left_buffer[..frames], &right_buffer[..frames])?;
+            if let Some(remaining) = remaining_frames.as_mut() {
+                *remaining -= frames;
+            }
+
+            if let Some(progress) = progress.as_mut() {
+                progress(player.progress());
+            }
+        }
+
+        if player.is_playing() {
+            return Ok(());
+        }
+
+        let tail_frames = (self.release_tail.tail_ms as u64 * self.sample_rate as u64 / 1000) as usize;
+        let tail_frames = remaining_frames.map_or(tail_frames, |remaining| remaining.min(tail_frames));
+        let mut rendered = 0;
+        while rendered < tail_frames {
+            stop.check()?;
+
+            let frames = (tail_frames - rendered).min(buffer_size);
+            let status = unsafe { S::write(self.synth, frames, &mut left_buffer, &mut right_buffer) };
+            if status != 0 {
+                break;
+            }
+
+            if self.release_tail.fade {
+                for index in 0..frames {
+                    let gain = 1.0 - (rendered + index + 1) as f32 / tail_frames as f32;
+                    left_buffer[index] = left_buffer[index].scale(gain);
+                    right_buffer[index] = right_buffer[index].scale(gain);
+                }
+            }
+            on_buffer(&left_buffer[..frames], &right_buffer[..frames])?;
+            rendered += frames;
+        }
+
+        Ok(())
+    }
+
+    /// Immediately stops every voice, including notes still held or releasing
+    /// when a render stopped early
+    fn silence(&mut self) {
+        unsafe {
+            fluid_synth_all_sounds_off(self.synth, -1);
+        }
+    }
+
+    /// Creates a player for `source` and starts it
+    fn start_player(&mut self, source: MidiSource<'_>) -> Result<FluidPlayer, ConversionError> {
+        self.require_soundfont()?;
+
+        // fluid_player_add_mem copies the data, so it only has to live until the call
+        let rewritten;
+        let source = if self.muted_channels != 0 || self.transpose != 0 || self.metronome.is_some() {
+            rewritten = self.rewrite_midi(source)?;
+            MidiSource::Bytes(&rewritten)
+        } else {
+            source
+        };
+
+        // SAFETY: the synth lives as long as `self`, and the player is only
+        // kept for the duration of a `&mut self` borrow
+        let mut player = unsafe { FluidPlayer::new(self.synth)? };
+        match source {
+            MidiSource::File(midi_path) => player.add(midi_path)?,
+            MidiSource::Bytes(midi_data) => player.add_mem(midi_data)?,
+        }
+        if self.tempo_scale != 1.0 {
+            player.set_tempo_scale(self.tempo_scale)?;
+        }
+        player.play()?;
+        Ok(player)
+    }
+
+    /// Returns the MIDI data of `source` transposed, with note-ons on muted
+    /// channels removed and the metronome clicks added
+    fn rewrite_midi(&mut self, source: MidiSource<'_>) -> Result<Vec<u8>, ConversionError> {
+        let file_data;
+        let midi_data = match source {
+            MidiSource::File(midi_path) => {
+                file_data = std::fs::read(midi_path)
+                    .map_err(|e| ConversionError::io("Failed to read MIDI file", e))?;
+                &file_data[..]
+            }
+            MidiSource::Bytes(midi_data) => midi_data,
+        };
+
+        let mut midi = MidiFile::parse(midi_data)?;
+        for track in &mut midi.tracks {
+            let events = midi_file::to_absolute(track)
+                .into_iter()
+                .filter(|(_, event)| {
+                    event.message_type() != Some(0x90)
+                        || event.channel_number().is_none_or(|chan| self.is_channel_enabled(chan))
+                })
+                .collect();
+            *track = midi_file::from_absolute(events);
+        }
+        self.warnings.extend(midi_file::clamped_notes_warning(midi.transpose(self.transpose), self.transpose));
+        if let Some(accent_note) = self.metronome {
+            midi.add_click_track(accent_note);
+        }
+        Ok(midi.to_bytes())
+    }
+}
+
+/// Incremental synthesis of a MIDI file, created by [`MidiConverter::synth_frames`]
+/// 
+/// Each call to `next()` renders one buffer of [`MidiConverter::buffer_size`] frames and
+/// returns it as separate left and right channels. Iteration ends when the
+/// player stops, or after the first error. The converter stays mutably
+/// borrowed until the iterator is dropped, which also stops the player
+/// and silences the synth.
+pub struct SynthFrames<'a> {
+    converter: &'a mut MidiConverter,
+    player: FluidPlayer,
+    finished: bool,
+}
+
+impl Iterator for SynthFrames<'_> {
+    type Item = Result<(Vec<i16>, Vec<i16>), ConversionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        if !self.player.is_playing() {
+            self.finished = true;
+            return None;
+        }
+
+        let buffer_size = self.converter.buffer_size;
+        let mut left = vec![0i16; buffer_size];
+        let mut right = vec![0i16; buffer_size];
+        if unsafe { i16::write(self.converter.synth, buffer_size, &mut left, &mut right) } != 0 {
+            self.finished = true;
+            return Some(Err(ConversionError::Synthesis("Failed to synthesize audio".to_string())));
+        }
+        Some(Ok((left, right)))
+    }
+}
+
+impl std::iter::FusedIterator for SynthFrames<'_> {}
+
+impl Drop for SynthFrames<'_> {
+    fn drop(&mut self) {
+        self.converter.silence();
+    }
+}
+
+/// Returns the bit of `chan` in a channel mask, checking its range
+fn channel_bit(chan: u8) -> Result<u16, ConversionError> {
+    if chan > 15 {
+        return Err(ConversionError::InvalidArgument(format!("Invalid MIDI channel {} (expected 0-15)", chan)));
+    }
+    Ok(1 << chan)
+}
+
+/// Converts a floating point sample (-1.0 to 1.0) to 24-bit PCM, clipping out-of-range values
+fn float_to_i24(sample: f32) -> i32 {
+    (sample.clamp(-1.0, 1.0) * I24_MAX).round() as i32
+}
+
+/// Applies linear fades to interleaved stereo samples
+/// 
+/// Fades longer than the audio are shortened to fit.
+fn apply_fades(samples: &mut [i16], fade_in_frames: usize, fade_out_frames: usize) {
+    let total_frames = samples.len() / 2;
+    let fade_in_frames = fade_in_frames.min(total_frames);
+    let fade_out_frames = fade_out_frames.min(total_frames);
+
+    for (index, frame) in samples.chunks_exact_mut(2).enumerate() {
+        let mut gain = 1.0;
+        if index < fade_in_frames {
+            gain *= index as f64 / fade_in_frames as f64;
+        }
+        let remaining = total_frames - 1 - index;
+        if remaining < fade_out_frames {
+            gain *= remaining as f64 / fade_out_frames as f64;
+        }
+        if gain < 1.0 {
+            for sample in frame {
+                *sample = (*sample as f64 * gain).round() as i16;
+            }
+        }
+    }
+}
+
+// SAFETY: `MidiConverter` exclusively owns its FluidSynth settings and synth;
+// the pointers are never handed out, and a player only lives within a single
+// `&mut self` call or in a `SynthFrames` holding the `&mut self` borrow.
+// FluidSynth objects have no thread affinity, so moving the sole owner to
+// another thread is sound. `Sync` is deliberately not implemented: every
+// method that touches the synth takes `&mut self`, so there is never
+// concurrent access from two threads.
+unsafe impl Send for MidiConverter {}
+
+impl Drop for MidiConverter {
+    fn drop(&mut self) {
+        // The synth refers to the settings, which are only dropped after this
+        unsafe {
+            if !self.synth.is_null() {
+                delete_fluid_synth(self.synth);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fluid_player::DROPPED_PLAYERS;
+    use std::io::{self, Cursor, SeekFrom};
+
+    /// One track playing middle C for two seconds at 120 BPM (480 ticks per beat)
+    const TWO_SECOND_NOTE: &[u8] = &[
+        b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xe0,
+        b'M', b'T', b'r', b'k', 0, 0, 0, 13,
+        0x00, 0x90, 60, 100,
+        0x8f, 0x00, 0x80, 60, 0,
+        0x00, 0xff, 0x2f, 0x00,
+    ];
+
+    /// Creates a converter that passes the SoundFont check without loading one
+    /// 
+    /// No .sf2 is available to tests. FluidSynth then renders silence, which
+    /// still exercises the player and the writing of every buffer.
+    fn converter_without_soundfont() -> MidiConverter {
+        let mut converter = MidiConverter::new().unwrap();
+        converter.soundfonts.push(0);
+        converter
+    }
+
+    /// In-memory sink whose writes fail once `limit` bytes have been written
+    struct FailingSink {
+        data: Cursor<Vec<u8>>,
+        limit: u64,
+    }
+
+    impl Write for FailingSink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.data.position() + buf.len() as u64 > self.limit {
+                return Err(io::Error::other("sink is full"));
+            }
+            self.data.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for FailingSink {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.data.seek(pos)
+        }
+    }
+
+    #[test]
+    fn converter_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<MidiConverter>();
+    }
+
+    #[test]
+    fn converter_renders_in_a_spawned_thread() {
+        let mut converter = converter_without_soundfont();
+        let samples = std::thread::spawn(move || converter.render_midi_bytes(TWO_SECOND_NOTE))
+            .join()
+            .unwrap()
+            .unwrap();
+        // The note lasts two seconds; allow for rendering in whole blocks
+        assert!(samples.len() > 2 * 3 * DEFAULT_SAMPLE_RATE as usize / 2);
+    }
+
+    #[test]
+    fn write_failure_during_synthesis_frees_the_player() {
+        let mut converter = converter_without_soundfont();
+        let sink = FailingSink { data: Cursor::new(Vec::new()), limit: 4096 };
+        let dropped_before = DROPPED_PLAYERS.load(Ordering::SeqCst);
+
+        let result = converter.render_wav(MidiSource::Bytes(TWO_SECOND_NOTE), sink, None, StopConditions::default());
+
+        assert!(matches!(result, Err(ConversionError::Wav { .. })), "unexpected result: {:?}", result.map(|_| ()));
+        assert!(DROPPED_PLAYERS.load(Ordering::SeqCst) > dropped_before);
+    }
+}
@@ -0,0 +1,901 @@
+/*!
+ * Standard MIDI File Module
+ *
+ * Minimal reader/writer for Standard MIDI Files (SMF), used to post-process
+ * the MIDI data generated by yks_converter before synthesis.
+ */
+
+use crate::error::ConversionError;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Meta event type: set tempo (microseconds per quarter note)
+pub const META_TEMPO: u8 = 0x51;
+
+/// Tempo assumed before the first tempo event (120 BPM), in microseconds per quarter note
+pub const DEFAULT_TEMPO: u32 = 500_000;
+
+/// Meta event type: end of track
+pub const META_END_OF_TRACK: u8 = 0x2f;
+
+/// Meta event type: time signature
+pub const META_TIME_SIGNATURE: u8 = 0x58;
+
+/// General MIDI percussion key of the metronome click on beats other than the downbeat (Low Wood Block)
+pub const METRONOME_CLICK_NOTE: u8 = 77;
+
+/// General MIDI percussion key suggested for the metronome downbeat (Hi Wood Block)
+pub const METRONOME_ACCENT_NOTE: u8 = 76;
+
+/// Channel (0-based) that General MIDI reserves for percussion
+pub const DRUM_CHANNEL: u8 = 9;
+
+/// Slowest accepted tempo scale (quarter speed)
+pub const MIN_TEMPO_SCALE: f64 = 0.25;
+
+/// Fastest accepted tempo scale (four times as fast)
+pub const MAX_TEMPO_SCALE: f64 = 4.0;
+
+/// Largest value a tempo event can hold (24 bits)
+const MAX_TEMPO: u32 = 0xff_ffff;
+
+/// A parsed Standard MIDI File
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MidiFile {
+    /// SMF format (0, 1 or 2)
+    pub format: u16,
+    /// Time division from the header; ticks per quarter note when the top bit is clear
+    pub division: u16,
+    /// Track chunks in file order
+    pub tracks: Vec<Vec<TrackEvent>>,
+}
+
+/// An event together with its delta time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackEvent {
+    /// Ticks since the previous event in the track
+    pub delta: u32,
+    /// The event itself
+    pub event: Event,
+}
+
+/// A MIDI track event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Channel voice message, e.g. note on/off or program change
+    Channel {
+        /// Status byte including the channel in its low nibble
+        status: u8,
+        /// One or two data bytes
+        data: Vec<u8>,
+    },
+    /// Meta event (tempo, track name, end of track, ...)
+    Meta {
+        /// Meta event type
+        kind: u8,
+        /// Event payload
+        data: Vec<u8>,
+    },
+    /// System exclusive message (`F0` or `F7`)
+    SysEx {
+        /// `0xF0` or `0xF7`
+        status: u8,
+        /// Message payload
+        data: Vec<u8>,
+    },
+}
+
+/// A suspicious pattern in MIDI data, as reported by [`MidiFile::lint`]
+/// 
+/// Channels are 0-based and ticks absolute, counted from the start of the song.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum MidiLint {
+    /// A note-on that no note-off releases, so it rings until the song ends
+    UnterminatedNote {
+        /// Channel of the note
+        channel: u8,
+        /// Note number
+        note: u8,
+        /// Tick of the note-on
+        tick: u64,
+    },
+    /// A note-on for a key that is already sounding on the same channel
+    OverlappingNote {
+        /// Channel of the note
+        channel: u8,
+        /// Note number
+        note: u8,
+        /// Tick of the second note-on
+        tick: u64,
+    },
+    /// Channel volume (CC 7) set to zero while notes still play on the channel
+    ZeroVolume {
+        /// Channel whose volume is zero
+        channel: u8,
+        /// Tick of the controller change
+        tick: u64,
+        /// Number of notes started before the volume is raised again
+        silenced_notes: usize,
+    },
+}
+
+impl fmt::Display for MidiLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MidiLint::UnterminatedNote { channel, note, tick } => {
+                write!(f, "channel {}, tick {}: note {} is never released and will drone until the end", channel, tick, note)
+            }
+            MidiLint::OverlappingNote { channel, note, tick } => {
+                write!(f, "channel {}, tick {}: note {} starts again while still sounding", channel, tick, note)
+            }
+            MidiLint::ZeroVolume { channel, tick, silenced_notes } => {
+                write!(f, "channel {}, tick {}: volume set to zero, silencing the next {} notes", channel, tick, silenced_notes)
+            }
+        }
+    }
+}
+
+impl Event {
+    /// Creates a channel message
+    pub fn channel(status: u8, channel: u8, data: &[u8]) -> Self {
+        Event::Channel { status: (status & 0xf0) | (channel & 0x0f), data: data.to_vec() }
+    }
+
+    /// Returns the message type (high nibble of the status byte) of a channel message
+    pub fn message_type(&self) -> Option<u8> {
+        match self {
+            Event::Channel { status, .. } => Some(status & 0xf0),
+            _ => None,
+        }
+    }
+
+    /// Returns the channel (0-15) of a channel message
+    pub fn channel_number(&self) -> Option<u8> {
+        match self {
+            Event::Channel { status, .. } => Some(status & 0x0f),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` for a note-on with non-zero velocity
+    pub fn is_note_on(&self) -> bool {
+        matches!(self, Event::Channel { status, data } if status & 0xf0 == 0x90 && data.get(1).is_some_and(|&v| v > 0))
+    }
+}
+
+impl MidiFile {
+    /// Parses Standard MIDI File data
+    /// 
+    /// # Arguments
+    /// 
+    /// * `data` - Contents of a `.mid` file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(MidiFile)` on success, or `Err(ConversionError)` if the data is malformed.
+    pub fn parse(data: &[u8]) -> Result<Self, ConversionError> {
+        let mut reader = Reader { data, pos: 0 };
+
+        if reader.take(4)? != b"MThd" {
+            return Err(invalid("missing MThd header"));
+        }
+        let header_len = reader.u32()? as usize;
+        let header = reader.take(header_len)?;
+        if header.len() < 6 {
+            return Err(invalid("header chunk too short"));
+        }
+        let format = u16::from_be_bytes([header[0], header[1]]);
+        let division = u16::from_be_bytes([header[4], header[5]]);
+
+        let mut tracks = Vec::new();
+        while reader.pos < data.len() {
+            let id = reader.take(4)?;
+            let len = reader.u32()? as usize;
+            let chunk = reader.take(len)?;
+            // Unknown chunk types must be skipped per the SMF specification
+            if id == b"MTrk" {
+                tracks.push(parse_track(chunk)?);
+            }
+        }
+
+        Ok(MidiFile { format, division, tracks })
+    }
+
+    /// Serializes the file back to Standard MIDI File bytes
+    /// 
+    /// Running status is not used, and an end-of-track event is appended to
+    /// any track that lacks one.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&self.format.to_be_bytes());
+        bytes.extend_from_slice(&(self.tracks.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.division.to_be_bytes());
+
+        for track in &self.tracks {
+            let mut chunk = Vec::new();
+            for event in track {
+                write_vlq(&mut chunk, event.delta);
+                write_event(&mut chunk, &event.event);
+            }
+            let terminated = matches!(track.last(), Some(TrackEvent { event: Event::Meta { kind: META_END_OF_TRACK, .. }, .. }));
+            if !terminated {
+                write_vlq(&mut chunk, 0);
+                write_event(&mut chunk, &Event::Meta { kind: META_END_OF_TRACK, data: Vec::new() });
+            }
+
+            bytes.extend_from_slice(b"MTrk");
+            bytes.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+            bytes.extend(chunk);
+        }
+
+        bytes
+    }
+
+    /// Returns the ticks per quarter note, or `None` for SMPTE time division
+    pub fn ticks_per_quarter(&self) -> Option<u16> {
+        (self.division & 0x8000 == 0).then_some(self.division)
+    }
+
+    /// Returns the tick of the last event across all tracks
+    pub fn end_tick(&self) -> u64 {
+        self.tracks
+            .iter()
+            .map(|track| track.iter().map(|event| event.delta as u64).sum())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns every tempo change as (absolute tick, microseconds per quarter note), sorted by tick
+    pub fn tempo_map(&self) -> Vec<(u64, u32)> {
+        let mut tempos: Vec<(u64, u32)> = self.tracks
+            .iter()
+            .flat_map(|track| to_absolute(track))
+            .filter_map(|(tick, event)| match event {
+                Event::Meta { kind: META_TEMPO, data } if data.len() == 3 => {
+                    Some((tick, u32::from_be_bytes([0, data[0], data[1], data[2]])))
+                }
+                _ => None,
+            })
+            .collect();
+        tempos.sort_by_key(|(tick, _)| *tick);
+        tempos
+    }
+
+    /// Returns every time signature change as (absolute tick, beats per bar,
+    /// beat unit), sorted by tick
+    /// 
+    /// The beat unit is the note value of one beat, e.g. 4 for quarter notes
+    /// in 3/4 or 8 for eighth notes in 6/8.
+    pub fn time_signatures(&self) -> Vec<(u64, u8, u32)> {
+        let mut signatures: Vec<(u64, u8, u32)> = self.tracks
+            .iter()
+            .flat_map(|track| to_absolute(track))
+            .filter_map(|(tick, event)| match event {
+                Event::Meta { kind: META_TIME_SIGNATURE, data } if data.len() >= 2 && data[0] > 0 => {
+                    Some((tick, data[0], 1u32 << data[1].min(6)))
+                }
+                _ => None,
+            })
+            .collect();
+        signatures.sort_by_key(|(tick, ..)| *tick);
+        signatures
+    }
+
+    /// Adds a track of metronome clicks on every beat
+    /// 
+    /// Beats follow the time signature events (4/4 until the first one), and
+    /// the beat count restarts at each change, which the SMF specification
+    /// places at bar lines. The first beat of each bar plays `accent_note`
+    /// louder; the others play [`METRONOME_CLICK_NOTE`]. Clicks go on
+    /// [`DRUM_CHANNEL`] and run until the last event of the song. Format 0
+    /// files become format 1, since the clicks get a track of their own.
+    /// SMPTE-timed files have no beats and are left unchanged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `accent_note` - Percussion key (0-127) struck on downbeats, e.g. [`METRONOME_ACCENT_NOTE`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the number of clicks added.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::midi_file::{Event, MidiFile, TrackEvent, META_TIME_SIGNATURE};
+    /// 
+    /// // One bar of 3/4 followed by a note ending after the second bar
+    /// let midi_track = vec![
+    ///     TrackEvent { delta: 0, event: Event::Meta { kind: META_TIME_SIGNATURE, data: vec![3, 2, 24, 8] } },
+    ///     TrackEvent { delta: 480 * 6, event: Event::channel(0x80, 0, &[60, 0]) },
+    /// ];
+    /// let mut midi = MidiFile { format: 0, division: 480, tracks: vec![midi_track] };
+    /// assert_eq!(midi.add_click_track(76), 6);
+    /// assert_eq!(midi.format, 1);
+    /// assert_eq!(midi.tracks.len(), 2);
+    /// ```
+    pub fn add_click_track(&mut self, accent_note: u8) -> usize {
+        let Some(ticks_per_quarter) = self.ticks_per_quarter() else {
+            return 0;
+        };
+        let ticks_per_quarter = ticks_per_quarter.max(1) as u64;
+        let end_tick = self.end_tick();
+        let signatures = self.meter_map();
+
+        let mut events = Vec::new();
+        let mut clicks = 0;
+        for (index, &(start, beats_per_bar, beat_unit)) in signatures.iter().enumerate() {
+            let end = signatures.get(index + 1).map_or(end_tick, |(tick, ..)| (*tick).min(end_tick));
+            let ticks_per_beat = (ticks_per_quarter * 4 / beat_unit as u64).max(1);
+            let click_length = (ticks_per_quarter / 8).max(1);
+            for (beat, tick) in (start..end).step_by(ticks_per_beat as usize).enumerate() {
+                let (note, velocity) = if beat % beats_per_bar as usize == 0 {
+                    (accent_note & 0x7f, 127)
+                } else {
+                    (METRONOME_CLICK_NOTE, 90)
+                };
+                events.push((tick, Event::channel(0x90, DRUM_CHANNEL, &[note, velocity])));
+                events.push((tick + click_length, Event::channel(0x80, DRUM_CHANNEL, &[note, 0])));
+                clicks += 1;
+            }
+        }
+
+        if clicks > 0 {
+            if self.format == 0 {
+                self.format = 1;
+            }
+            self.tracks.push(from_absolute(events));
+        }
+        clicks
+    }
+
+    /// Returns the start tick of every bar that begins before the end of the song
+    /// 
+    /// Bars follow the time signature events (4/4 until the first one). A
+    /// time signature change inside a bar closes that bar early, so a pickup
+    /// written as a short opening bar comes out as a bar of its own. Tempo
+    /// changes do not move bar lines. SMPTE-timed files have no bars.
+    pub fn bar_starts(&self) -> Vec<u64> {
+        self.bars().into_iter().map(|(tick, _)| tick).collect()
+    }
+
+    /// Returns the tick range from the start of `start_bar` to the end of `end_bar`
+    /// 
+    /// Bars are numbered from 1 as in a score. If the song opens with a
+    /// pickup, i.e. a first bar shorter than the one after it, the pickup is
+    /// bar 0 and bar 1 is the first full bar.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start_bar` - First bar to include
+    /// * `end_bar` - Last bar to include
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok((start_tick, end_tick))` on success, or `Err(ConversionError)` if
+    /// the range is empty or reaches past the last bar of the song.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::midi_file::{Event, MidiFile, TrackEvent, META_TIME_SIGNATURE};
+    /// 
+    /// let time_signature = |delta, numerator| TrackEvent {
+    ///     delta,
+    ///     event: Event::Meta { kind: META_TIME_SIGNATURE, data: vec![numerator, 2, 24, 8] },
+    /// };
+    /// // A one-beat pickup, then three bars of 4/4
+    /// let track = vec![
+    ///     time_signature(0, 1),
+    ///     time_signature(480, 4),
+    ///     TrackEvent { delta: 480 * 12, event: Event::channel(0x80, 0, &[60, 0]) },
+    /// ];
+    /// let midi = MidiFile { format: 0, division: 480, tracks: vec![track] };
+    /// assert_eq!(midi.bar_range(0, 0)?, (0, 480));
+    /// assert_eq!(midi.bar_range(2, 3)?, (480 + 1920, 480 + 1920 * 3));
+    /// assert!(midi.bar_range(2, 4).is_err());
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn bar_range(&self, start_bar: u32, end_bar: u32) -> Result<(u64, u64), ConversionError> {
+        let bars = self.bars();
+        if bars.is_empty() {
+            return Err(ConversionError::InvalidArgument("The song has no bars to select".to_string()));
+        }
+
+        let pickup = match bars.as_slice() {
+            [(first, _), (second, length), ..] => second - first < *length,
+            _ => false,
+        };
+        let first_bar = if pickup { 0 } else { 1 };
+        let last_bar = bars.len() as u32 - 1 + first_bar;
+
+        if start_bar > end_bar {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Start bar {} is after end bar {}",
+                start_bar, end_bar
+            )));
+        }
+        if start_bar < first_bar {
+            return Err(ConversionError::InvalidArgument(
+                "Bar 0 only exists in songs that open with a pickup; bars are numbered from 1".to_string(),
+            ));
+        }
+        if end_bar > last_bar {
+            return Err(ConversionError::InvalidArgument(format!(
+                "End bar {} is past the end of the song, whose last bar is {}",
+                end_bar, last_bar
+            )));
+        }
+
+        let start_index = (start_bar - first_bar) as usize;
+        let end_index = (end_bar - first_bar) as usize;
+        let end_tick = bars.get(end_index + 1).map_or_else(|| bars[end_index].0 + bars[end_index].1, |(tick, _)| *tick);
+        Ok((bars[start_index].0, end_tick))
+    }
+
+    /// Cuts the events between two ticks into a new file starting at tick 0
+    /// 
+    /// Tempo, program, controller and other state set before `start` is moved
+    /// to tick 0, so the excerpt sounds as it does in the full song. Notes
+    /// starting before `start` are dropped. Every channel gets its sustain
+    /// pedal released and all notes off at `end`, which also fixes the length
+    /// of the excerpt.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start` - First tick to include
+    /// * `end` - Tick the excerpt ends at (exclusive)
+    pub fn extract_ticks(&self, start: u64, end: u64) -> MidiFile {
+        let end = end.max(start);
+        let tracks = self.tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| {
+                let mut events: Vec<(u64, Event)> = to_absolute(track)
+                    .into_iter()
+                    .filter_map(|(tick, event)| {
+                        let is_note = matches!(event.message_type(), Some(0x80 | 0x90 | 0xa0));
+                        let is_end = matches!(event, Event::Meta { kind: META_END_OF_TRACK, .. });
+                        match tick {
+                            _ if is_end || tick >= end => None,
+                            _ if tick >= start => Some((tick - start, event)),
+                            _ if is_note => None,
+                            _ => Some((0, event)),
+                        }
+                    })
+                    .collect();
+                if index == 0 {
+                    for channel in 0..16 {
+                        events.push((end - start, Event::channel(0xb0, channel, &[64, 0])));
+                        events.push((end - start, Event::channel(0xb0, channel, &[123, 0])));
+                    }
+                }
+                from_absolute(events)
+            })
+            .collect();
+
+        MidiFile { format: self.format, division: self.division, tracks }
+    }
+
+    /// Returns the time signature changes, starting with 4/4 at tick 0 unless
+    /// the file sets its own there
+    fn meter_map(&self) -> Vec<(u64, u8, u32)> {
+        let mut signatures = self.time_signatures();
+        if signatures.first().is_none_or(|(tick, ..)| *tick > 0) {
+            signatures.insert(0, (0, 4, 4));
+        }
+        signatures
+    }
+
+    /// Returns (start tick, full length in ticks) of every bar, see [`MidiFile::bar_starts`]
+    fn bars(&self) -> Vec<(u64, u64)> {
+        let Some(ticks_per_quarter) = self.ticks_per_quarter() else {
+            return Vec::new();
+        };
+        let ticks_per_quarter = ticks_per_quarter.max(1) as u64;
+        let end_tick = self.end_tick();
+
+        let signatures = self.meter_map();
+        let mut bars = Vec::new();
+        for (index, &(start, beats_per_bar, beat_unit)) in signatures.iter().enumerate() {
+            let end = signatures.get(index + 1).map_or(end_tick, |(tick, ..)| (*tick).min(end_tick));
+            let length = (ticks_per_quarter * 4 / beat_unit as u64 * beats_per_bar as u64).max(1);
+            bars.extend((start..end).step_by(length as usize).map(|tick| (tick, length)));
+        }
+        bars
+    }
+
+    /// Checks for notes that are never released or restart while sounding,
+    /// and channels whose volume is zero while they play
+    /// 
+    /// Tracks are merged in tick order first, since note state belongs to
+    /// channels rather than tracks. A note-on with velocity 0 counts as a
+    /// note-off.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns every problem found, ordered by tick.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::midi_file::{Event, MidiFile, MidiLint, TrackEvent};
+    /// 
+    /// let event = |delta, status, data: &[u8]| TrackEvent { delta, event: Event::channel(status, 0, data) };
+    /// let track = vec![event(0, 0x90, &[60, 100]), event(480, 0x80, &[60, 0]), event(0, 0x90, &[64, 100])];
+    /// let midi = MidiFile { format: 0, division: 480, tracks: vec![track] };
+    /// assert_eq!(midi.lint(), vec![MidiLint::UnterminatedNote { channel: 0, note: 64, tick: 480 }]);
+    /// ```
+    pub fn lint(&self) -> Vec<MidiLint> {
+        let mut events: Vec<(u64, Event)> = self.tracks.iter().flat_map(|track| to_absolute(track)).collect();
+        events.sort_by_key(|(tick, _)| *tick);
+
+        let mut lints = Vec::new();
+        let mut sounding: HashMap<(u8, u8), Vec<u64>> = HashMap::new();
+        // Index into `lints` of the zero-volume lint still in effect on each channel
+        let mut zero_volume: [Option<usize>; 16] = [None; 16];
+
+        for (tick, event) in &events {
+            let (Event::Channel { data, .. }, Some(channel)) = (event, event.channel_number()) else {
+                continue;
+            };
+            let Some(&key) = data.first() else { continue };
+
+            match event.message_type() {
+                Some(0x90) if event.is_note_on() => {
+                    let starts = sounding.entry((channel, key)).or_default();
+                    if !starts.is_empty() {
+                        lints.push(MidiLint::OverlappingNote { channel, note: key, tick: *tick });
+                    }
+                    starts.push(*tick);
+                    if let Some(MidiLint::ZeroVolume { silenced_notes, .. }) =
+                        zero_volume[channel as usize].and_then(|index| lints.get_mut(index))
+                    {
+                        *silenced_notes += 1;
+                    }
+                }
+                Some(0x80 | 0x90) => {
+                    if let Some(starts) = sounding.get_mut(&(channel, key)) {
+                        starts.pop();
+                    }
+                }
+                Some(0xb0) if key == 7 => {
+                    let volume = data.get(1).copied().unwrap_or(0);
+                    zero_volume[channel as usize] = (volume == 0).then(|| {
+                        lints.push(MidiLint::ZeroVolume { channel, tick: *tick, silenced_notes: 0 });
+                        lints.len() - 1
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        lints.retain(|lint| !matches!(lint, MidiLint::ZeroVolume { silenced_notes: 0, .. }));
+        for ((channel, note), starts) in sounding {
+            lints.extend(starts.into_iter().map(|tick| MidiLint::UnterminatedNote { channel, note, tick }));
+        }
+        lints.sort_by_key(|lint| match lint {
+            MidiLint::UnterminatedNote { channel, note, tick } | MidiLint::OverlappingNote { channel, note, tick } => (*tick, *channel, *note),
+            MidiLint::ZeroVolume { channel, tick, .. } => (*tick, *channel, 0),
+        });
+        lints
+    }
+
+    /// Converts an absolute tick to seconds, following the tempo map
+    /// 
+    /// The tempo defaults to 120 BPM until the first tempo event, as the SMF
+    /// specification requires.
+    pub fn tick_to_secs(&self, tick: u64) -> f64 {
+        let Some(ticks_per_quarter) = self.ticks_per_quarter() else {
+            // SMPTE division: negative frames per second in the high byte, ticks per frame in the low byte
+            let frames_per_second = -(((self.division >> 8) as u8 as i8) as f64);
+            let ticks_per_frame = (self.division & 0xff) as f64;
+            return tick as f64 / (frames_per_second * ticks_per_frame);
+        };
+
+        let ticks_per_quarter = ticks_per_quarter.max(1) as f64;
+        let mut seconds = 0.0;
+        let mut last_tick = 0;
+        let mut tempo = DEFAULT_TEMPO as f64;
+        for (change_tick, new_tempo) in self.tempo_map() {
+            if change_tick >= tick {
+                break;
+            }
+            seconds += (change_tick - last_tick) as f64 * tempo / 1_000_000.0 / ticks_per_quarter;
+            last_tick = change_tick;
+            tempo = new_tempo as f64;
+        }
+
+        seconds + (tick - last_tick) as f64 * tempo / 1_000_000.0 / ticks_per_quarter
+    }
+
+    /// Returns the playback length in seconds
+    pub fn duration_secs(&self) -> f64 {
+        self.tick_to_secs(self.end_tick())
+    }
+
+    /// Speeds up (`scale` > 1.0) or slows down (`scale` < 1.0) playback
+    /// 
+    /// Every tempo event is rewritten, so tempo changes in the middle of the
+    /// song are kept and scaled by the same factor. If no tempo event sits at
+    /// tick 0, one carrying the scaled default of 120 BPM is added to the first
+    /// track. SMPTE-timed files have no tempo events and are left unchanged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - Speed factor between [`MIN_TEMPO_SCALE`] and [`MAX_TEMPO_SCALE`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the scale is out of range.
+    pub fn scale_tempo(&mut self, scale: f64) -> Result<(), ConversionError> {
+        validate_tempo_scale(scale)?;
+        if self.ticks_per_quarter().is_none() {
+            return Ok(());
+        }
+
+        let scaled = |tempo: u32| ((tempo as f64 / scale).round() as u32).clamp(1, MAX_TEMPO);
+        let starts_with_tempo = self.tempo_map().first().is_some_and(|(tick, _)| *tick == 0);
+
+        for track in &mut self.tracks {
+            for event in track.iter_mut() {
+                if let Event::Meta { kind: META_TEMPO, data } = &mut event.event {
+                    if data.len() == 3 {
+                        let tempo = scaled(u32::from_be_bytes([0, data[0], data[1], data[2]]));
+                        data.copy_from_slice(&tempo.to_be_bytes()[1..]);
+                    }
+                }
+            }
+        }
+
+        if !starts_with_tempo {
+            if let Some(track) = self.tracks.first_mut() {
+                let tempo = scaled(DEFAULT_TEMPO);
+                track.insert(0, TrackEvent {
+                    delta: 0,
+                    event: Event::Meta { kind: META_TEMPO, data: tempo.to_be_bytes()[1..].to_vec() },
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shifts every note by `semitones`, clamping to the MIDI note range
+    /// 
+    /// Note on, note off and polyphonic aftertouch messages are shifted on all
+    /// channels except [`DRUM_CHANNEL`], where note numbers select drum sounds
+    /// rather than pitches. Notes that would leave 0-127 are clamped to the
+    /// nearest end of the range instead of wrapping around.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `semitones` - Interval to shift by; negative values transpose down
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the number of note-on events that had to be clamped.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::midi_file::{Event, MidiFile, TrackEvent};
+    /// 
+    /// let note_on = |channel, note| TrackEvent { delta: 0, event: Event::channel(0x90, channel, &[note, 100]) };
+    /// let mut midi = MidiFile { format: 0, division: 480, tracks: vec![vec![note_on(0, 60), note_on(0, 120), note_on(9, 36)]] };
+    /// assert_eq!(midi.transpose(12), 1);
+    /// let notes: Vec<u8> = midi.tracks[0].iter().map(|event| match &event.event {
+    ///     Event::Channel { data, .. } => data[0],
+    ///     _ => unreachable!(),
+    /// }).collect();
+    /// assert_eq!(notes, vec![72, 127, 36]);
+    /// ```
+    pub fn transpose(&mut self, semitones: i8) -> usize {
+        let mut clamped = 0;
+        if semitones == 0 {
+            return clamped;
+        }
+
+        for track in &mut self.tracks {
+            for event in track.iter_mut() {
+                let is_note = matches!(event.event.message_type(), Some(0x80 | 0x90 | 0xa0));
+                if !is_note || event.event.channel_number() == Some(DRUM_CHANNEL) {
+                    continue;
+                }
+                let note_on = event.event.is_note_on();
+                if let Event::Channel { data, .. } = &mut event.event {
+                    let Some(note) = data.first_mut() else { continue };
+                    let shifted = *note as i16 + semitones as i16;
+                    if note_on && !(0..=127).contains(&shifted) {
+                        clamped += 1;
+                    }
+                    *note = shifted.clamp(0, 127) as u8;
+                }
+            }
+        }
+
+        clamped
+    }
+}
+
+/// Checks that a tempo scale is within [`MIN_TEMPO_SCALE`]..=[`MAX_TEMPO_SCALE`]
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the accepted range.
+pub fn validate_tempo_scale(scale: f64) -> Result<(), ConversionError> {
+    if (MIN_TEMPO_SCALE..=MAX_TEMPO_SCALE).contains(&scale) {
+        Ok(())
+    } else {
+        Err(ConversionError::InvalidArgument(format!(
+            "Tempo scale must be between {} and {}, got {}",
+            MIN_TEMPO_SCALE, MAX_TEMPO_SCALE, scale
+        )))
+    }
+}
+
+/// Describes the notes clamped by [`MidiFile::transpose`], or `None` if there were none
+pub(crate) fn clamped_notes_warning(clamped: usize, semitones: i8) -> Option<String> {
+    (clamped > 0).then(|| format!(
+        "transposing by {} semitones moved {} notes outside the MIDI range; they were clamped to the nearest valid note",
+        semitones, clamped
+    ))
+}
+
+/// Converts a track to (absolute tick, event) pairs
+pub fn to_absolute(track: &[TrackEvent]) -> Vec<(u64, Event)> {
+    let mut tick = 0u64;
+    track
+        .iter()
+        .map(|event| {
+            tick += event.delta as u64;
+            (tick, event.event.clone())
+        })
+        .collect()
+}
+
+/// Builds a track from (absolute tick, event) pairs
+/// 
+/// Events are stably sorted by tick, so events sharing a tick keep their order.
+pub fn from_absolute(mut events: Vec<(u64, Event)>) -> Vec<TrackEvent> {
+    events.sort_by_key(|(tick, _)| *tick);
+    let mut previous = 0u64;
+    events
+        .into_iter()
+        .map(|(tick, event)| {
+            let delta = (tick - previous).min(0x0fff_ffff) as u32;
+            previous = tick;
+            TrackEvent { delta, event }
+        })
+        .collect()
+}
+
+fn invalid(message: &str) -> ConversionError {
+    ConversionError::InvalidMidi(format!("Invalid MIDI data: {}", message))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ConversionError> {
+        let end = self.pos.checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| invalid("unexpected end of data"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, ConversionError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ConversionError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn vlq(&mut self) -> Result<u32, ConversionError> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("variable-length quantity too long"))
+    }
+}
+
+fn parse_track(chunk: &[u8]) -> Result<Vec<TrackEvent>, ConversionError> {
+    let mut reader = Reader { data: chunk, pos: 0 };
+    let mut events = Vec::new();
+    let mut running_status = None;
+
+    while reader.pos < chunk.len() {
+        let delta = reader.vlq()?;
+        let first = reader.u8()?;
+
+        let event = match first {
+            0xff => {
+                let kind = reader.u8()?;
+                let len = reader.vlq()? as usize;
+                Event::Meta { kind, data: reader.take(len)?.to_vec() }
+            }
+            0xf0 | 0xf7 => {
+                running_status = None;
+                let len = reader.vlq()? as usize;
+                Event::SysEx { status: first, data: reader.take(len)?.to_vec() }
+            }
+            _ => {
+                let (status, first_data) = if first & 0x80 != 0 {
+                    running_status = Some(first);
+                    (first, None)
+                } else {
+                    let status = running_status.ok_or_else(|| invalid("data byte without running status"))?;
+                    (status, Some(first))
+                };
+                let len = channel_data_len(status);
+                let mut data = Vec::with_capacity(len);
+                if let Some(byte) = first_data {
+                    data.push(byte);
+                }
+                while data.len() < len {
+                    data.push(reader.u8()?);
+                }
+                Event::Channel { status, data }
+            }
+        };
+
+        let end_of_track = matches!(event, Event::Meta { kind: META_END_OF_TRACK, .. });
+        events.push(TrackEvent { delta, event });
+        if end_of_track {
+            break;
+        }
+    }
+
+    Ok(events)
+}
+
+fn channel_data_len(status: u8) -> usize {
+    match status & 0xf0 {
+        0xc0 | 0xd0 => 1,
+        _ => 2,
+    }
+}
+
+fn write_vlq(out: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7f) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7f) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+fn write_event(out: &mut Vec<u8>, event: &Event) {
+    match event {
+        Event::Channel { status, data } => {
+            out.push(*status);
+            out.extend_from_slice(data);
+        }
+        Event::Meta { kind, data } => {
+            out.push(0xff);
+            out.push(*kind);
+            write_vlq(out, data.len() as u32);
+            out.extend_from_slice(data);
+        }
+        Event::SysEx { status, data } => {
+            out.push(*status);
+            write_vlq(out, data.len() as u32);
+            out.extend_from_slice(data);
+        }
+    }
+}
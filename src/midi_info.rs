@@ -0,0 +1,152 @@
+/*!
+ * MIDI File Information
+ *
+ * Reads the header, track names and tempo map of a Standard MIDI File for
+ * display. Only the file is parsed; FluidSynth is not involved.
+ */
+
+use crate::error::ConversionError;
+use crate::midi_file::{self, Event, MidiFile};
+use std::fmt;
+
+/// Meta event type: sequence or track name
+pub const META_TRACK_NAME: u8 = 0x03;
+
+/// Summary of a MIDI file, as returned by [`read_midi_info`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MidiInfo {
+    /// SMF format (0 single track, 1 simultaneous tracks, 2 independent sequences)
+    pub format: u16,
+    /// Ticks per quarter note, or `None` for SMPTE time division
+    pub ticks_per_quarter: Option<u16>,
+    /// Name of each track in file order, `None` where the track has no name event
+    pub track_names: Vec<Option<String>>,
+    /// Every tempo change in playback order
+    pub tempo_map: Vec<TempoChange>,
+    /// Playback length in seconds, not including reverb tails
+    pub duration_secs: f64,
+}
+
+/// A tempo change in a MIDI file
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TempoChange {
+    /// Absolute tick of the change
+    pub tick: u64,
+    /// Playback time of the change in seconds
+    pub secs: f64,
+    /// New tempo in beats (quarter notes) per minute
+    pub bpm: f64,
+}
+
+impl MidiInfo {
+    /// Returns the number of track chunks
+    pub fn track_count(&self) -> usize {
+        self.track_names.len()
+    }
+
+    /// Summarizes parsed MIDI data
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::midi_file::{Event, MidiFile, TrackEvent, META_TEMPO};
+    /// use yks_converter_example::midi_info::{MidiInfo, META_TRACK_NAME};
+    /// 
+    /// let meta = |kind, data: &[u8]| TrackEvent { delta: 0, event: Event::Meta { kind, data: data.to_vec() } };
+    /// let midi = MidiFile {
+    ///     format: 1,
+    ///     division: 480,
+    ///     // 500000 microseconds per quarter note is 120 BPM
+    ///     tracks: vec![vec![meta(META_TRACK_NAME, b"Melody"), meta(META_TEMPO, &[0x07, 0xa1, 0x20])], vec![]],
+    /// };
+    /// let info = MidiInfo::from_midi(&midi);
+    /// assert_eq!(info.track_names, vec![Some("Melody".to_string()), None]);
+    /// assert_eq!(info.tempo_map[0].bpm, 120.0);
+    /// ```
+    pub fn from_midi(midi: &MidiFile) -> Self {
+        let track_names = midi.tracks
+            .iter()
+            .map(|track| {
+                track.iter().find_map(|event| match &event.event {
+                    Event::Meta { kind: META_TRACK_NAME, data } => {
+                        Some(String::from_utf8_lossy(data).trim().to_string())
+                    }
+                    _ => None,
+                })
+            })
+            .collect();
+        let tempo_map = midi.tempo_map()
+            .into_iter()
+            .map(|(tick, tempo)| TempoChange {
+                tick,
+                secs: midi.tick_to_secs(tick),
+                bpm: 60_000_000.0 / tempo.max(1) as f64,
+            })
+            .collect();
+
+        MidiInfo {
+            format: midi.format,
+            ticks_per_quarter: midi.ticks_per_quarter(),
+            track_names,
+            tempo_map,
+            duration_secs: midi.duration_secs(),
+        }
+    }
+}
+
+impl fmt::Display for MidiInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📊 MIDI File Info:")?;
+        writeln!(f, "• Format: {}", self.format)?;
+        writeln!(f, "• Tracks: {}", self.track_count())?;
+        match self.ticks_per_quarter {
+            Some(ticks) => writeln!(f, "• Ticks per quarter note: {}", ticks)?,
+            None => writeln!(f, "• Time division: SMPTE")?,
+        }
+        writeln!(f, "• Duration: {:.1} s", self.duration_secs)?;
+
+        writeln!(f, "• Track names:")?;
+        for (index, name) in self.track_names.iter().enumerate() {
+            writeln!(f, "  {:>3}  {}", index, name.as_deref().unwrap_or("(unnamed)"))?;
+        }
+
+        write!(f, "• Tempo map:")?;
+        if self.tempo_map.is_empty() {
+            let default_bpm = 60_000_000.0 / midi_file::DEFAULT_TEMPO as f64;
+            write!(f, " none ({:.0} BPM throughout)", default_bpm)?;
+        }
+        for change in &self.tempo_map {
+            write!(f, "\n  {:>8.2} s  tick {:>7}  {:.2} BPM", change.secs, change.tick, change.bpm)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the header, track names and tempo map of a MIDI file
+/// 
+/// # Arguments
+/// 
+/// * `midi_path` - Path to the MIDI file (.mid, .midi)
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(MidiInfo)` on success, or `Err(ConversionError)` with error message.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use yks_converter_example::midi_info::read_midi_info;
+/// 
+/// let info = read_midi_info("song.mid")?;
+/// for change in &info.tempo_map {
+///     println!("{:.1} s: {:.0} BPM", change.secs, change.bpm);
+/// }
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
+/// ```
+pub fn read_midi_info(midi_path: &str) -> Result<MidiInfo, ConversionError> {
+    let midi_data = std::fs::read(midi_path)
+        .map_err(|e| ConversionError::io(format!("Failed to read MIDI file '{}'", midi_path), e))?;
+    Ok(MidiInfo::from_midi(&MidiFile::parse(&midi_data)?))
+}
\ No newline at end of file
@@ -5,10 +5,24 @@
  * to MIDI format using the yks_converter library.
  */
 
+use crate::error::ConversionError;
+use crate::midi_file::{self, Event, MidiFile, MidiLint, TrackEvent};
+use crate::mml_syntax::{self, TokenKind, DEFAULT_OCTAVE, DEFAULT_TEMPO_BPM};
 use yks_converter::YksConverter;
+use std::borrow::Cow;
 use std::fs;
+use std::io;
 use std::path::Path;
 
+/// Largest count accepted after a repeat bracket, e.g. `[cde]99`
+pub const MAX_REPEAT_COUNT: u32 = 99;
+
+/// Largest MML length in bytes that repeat expansion may produce
+/// 
+/// Nested repeats multiply, so a short input like `[[[[[c]99]99]99]99]99`
+/// would otherwise expand to gigabytes.
+pub const MAX_EXPANDED_LEN: usize = 1 << 20;
+
 /// MML to MIDI converter using yks_converter library
 /// 
 /// This converter handles MML files from Mabinogi online game and converts
@@ -22,14 +36,105 @@ use std::path::Path;
 /// let converter = MmlConverter::new();
 /// converter.convert_mml_file_to_midi("song.mml", "output.mid").unwrap();
 /// ```
+#[derive(Clone)]
 pub struct MmlConverter {
     instrument: u8,
+    voice_instruments: Vec<u8>,
+    part_volumes: Vec<u8>,
+    bank: Option<u16>,
+    lenient: bool,
+    tempo_scale: f64,
+    default_tempo: Option<u16>,
+    default_octave: Option<u8>,
+    transpose: i8,
+    expand_repeats: bool,
+}
+
+/// Outcome of an MML conversion, with the warnings it produced
+/// 
+/// yks_converter is all-or-nothing: it either converts the whole MML text or
+/// returns nothing, without saying where it failed. Lenient conversion works
+/// around this by locating the longest prefix of the input that still converts.
+/// 
+/// Nothing is printed during conversion; the caller decides whether and
+/// where to show the warnings.
+#[derive(Debug, Clone)]
+pub struct LenientConversion {
+    /// MIDI data for the longest convertible part of the input
+    pub midi_data: Vec<u8>,
+    /// Warnings such as where parsing stopped or how many transposed notes
+    /// were clamped; empty if the whole input converted cleanly
+    pub warnings: Vec<String>,
+}
+
+/// Structure of an MML text, as reported by [`MmlConverter::analyze`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MmlAnalysis {
+    /// Number of notes in each comma-separated part; tied continuations are not counted
+    pub notes_per_part: Vec<usize>,
+    /// Tempo in BPM set by the first `t` command, or 120 if there is none
+    pub tempo: u32,
+    /// Octave the first part starts playing in (4 unless set before its first note)
+    pub default_octave: u32,
+}
+
+impl MmlAnalysis {
+    /// Returns the number of comma-separated parts (voices)
+    pub fn part_count(&self) -> usize {
+        self.notes_per_part.len()
+    }
+
+    /// Returns the total number of notes across all parts
+    pub fn total_notes(&self) -> usize {
+        self.notes_per_part.iter().sum()
+    }
+}
+
+/// A single problem found while validating MML
+/// 
+/// `line` and `column` are 1-based and point at the offending command, so
+/// editors can underline it. Both are 0 when the issue applies to the whole
+/// file rather than a specific position (e.g. the file could not be read).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MmlValidationError {
+    /// Line of the offending token
+    pub line: usize,
+    /// Column of the offending token
+    pub column: usize,
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+/// Former name of [`MmlValidationError`]
+pub type ValidationIssue = MmlValidationError;
+
+impl std::fmt::Display for MmlValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+        }
+    }
 }
 
 impl MmlConverter {
     /// Creates a new MML converter instance with default instrument (0)
     pub fn new() -> Self {
-        MmlConverter { instrument: 0 }
+        MmlConverter {
+            instrument: 0,
+            voice_instruments: Vec::new(),
+            part_volumes: Vec::new(),
+            bank: None,
+            lenient: false,
+            tempo_scale: 1.0,
+            default_tempo: None,
+            default_octave: None,
+            transpose: 0,
+            expand_repeats: false,
+        }
     }
 
     /// Sets the instrument for MML conversion
@@ -38,7 +143,276 @@ impl MmlConverter {
     /// 
     /// * `instrument` - MIDI instrument number (0-127)
     pub fn set_instrument(&mut self, instrument: u8) {
+        self.set_instrument_with_bank(instrument, None);
+    }
+
+    /// Sets the instrument for MML conversion, optionally on a bank other than 0
+    /// 
+    /// When a bank is given, bank select messages (CC 0 and CC 32) are inserted
+    /// before every program change in the generated MIDI. The bank also applies
+    /// to instruments set with [`MmlConverter::set_instruments`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `instrument` - MIDI instrument number (0-127)
+    /// * `bank` - Bank number (0-16383), or `None` for the default bank
+    pub fn set_instrument_with_bank(&mut self, instrument: u8, bank: Option<u16>) {
         self.instrument = instrument;
+        self.voice_instruments.clear();
+        self.bank = bank.map(|bank| bank & 0x3fff);
+    }
+
+    /// Assigns each comma-separated voice its own MIDI channel and instrument
+    /// 
+    /// Voice N is played on channel N (skipping channel 9, which General MIDI
+    /// reserves for drums) with program `instruments[N]`. If fewer instruments
+    /// than voices are given, the last one is reused for the remaining voices.
+    /// 
+    /// Voices are identified as the note-carrying tracks in the MIDI generated by
+    /// yks_converter, which emits one track per comma-separated part.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `instruments` - MIDI program numbers (0-127), one per voice; an empty
+    ///   slice restores single-instrument rendering
+    pub fn set_instruments(&mut self, instruments: &[u8]) {
+        if let Some(&first) = instruments.first() {
+            self.instrument = first;
+        }
+        self.voice_instruments = instruments.to_vec();
+    }
+
+    /// Sets the channel volume of each comma-separated voice
+    /// 
+    /// The generated MIDI is post-processed to insert a channel volume
+    /// controller (CC 7) at the start of each voice's track; note velocities
+    /// are left alone. Because CC 7 applies to a whole channel, setting part
+    /// volumes also moves every voice to its own channel, exactly as
+    /// [`MmlConverter::set_instruments`] does. Without per-voice instruments all
+    /// voices keep the single instrument from [`MmlConverter::set_instrument`].
+    /// If fewer volumes than voices are given, the last one is reused.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `volumes` - Channel volumes (0-127, values above are clamped), one per
+    ///   voice; an empty slice leaves volumes untouched
+    pub fn set_part_volumes(&mut self, volumes: &[u8]) {
+        self.part_volumes = volumes.iter().map(|&volume| volume.min(127)).collect();
+    }
+
+    /// Enables or disables lenient conversion
+    /// 
+    /// In lenient mode, MML that fails partway through is still converted up to
+    /// the failure point instead of returning an error. The warning is returned
+    /// in [`LenientConversion::warnings`] by
+    /// [`MmlConverter::convert_mml_to_midi_reporting`];
+    /// [`MmlConverter::convert_mml_to_midi_bytes`] discards it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `lenient` - `true` to keep partial output on parse failures
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Plays the generated MIDI faster or slower without changing pitch
+    /// 
+    /// The tempo events in the generated MIDI are rewritten, so `t` commands
+    /// in the middle of the MML are kept and scaled by the same factor: with a
+    /// scale of 0.75, `t120` plays at 90 BPM and a later `t160` at 120 BPM.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - Speed factor from 0.25 to 4.0, e.g. 0.75 for practice at
+    ///   three-quarter speed; 1.0 leaves the tempo untouched
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the scale is out of range.
+    pub fn set_tempo_scale(&mut self, scale: f64) -> Result<(), ConversionError> {
+        midi_file::validate_tempo_scale(scale)?;
+        self.tempo_scale = scale;
+        Ok(())
+    }
+
+    /// Transposes the generated MIDI by a number of semitones
+    /// 
+    /// See [`MidiFile::transpose`]: notes pushed outside the MIDI range are
+    /// clamped, and [`MmlConverter::convert_mml_to_midi_reporting`] returns a
+    /// warning with the number of clamped notes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `semitones` - Interval to shift by, e.g. -2 to go down a whole tone; 0 disables transposition
+    pub fn set_transpose(&mut self, semitones: i8) {
+        self.transpose = semitones;
+    }
+
+    /// Expands `[...]n` repeat brackets before conversion
+    /// 
+    /// Off by default. While it is off, [`validate_mml`](Self::validate_mml)
+    /// rejects `[` and `]` as unexpected characters, so MML using repeat
+    /// brackets only converts with this enabled. When enabled, validation
+    /// accepts the brackets and reports unbalanced ones; see
+    /// [`MmlConverter::expand_repeats`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `expand` - Whether to expand repeat brackets
+    pub fn set_expand_repeats(&mut self, expand: bool) {
+        self.expand_repeats = expand;
+    }
+
+    /// Writes out `[...]n` repeat brackets as literal repetitions
+    /// 
+    /// The section between the brackets is played `n` times (1 to
+    /// [`MAX_REPEAT_COUNT`]), or twice without a count. Repeats can be nested,
+    /// but must open and close within one comma-separated part, and the
+    /// expanded MML may not exceed [`MAX_EXPANDED_LEN`] bytes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(String)` with the expanded MML, or `Err(ConversionError)` giving
+    /// the line and column of an unbalanced bracket, an invalid count or a
+    /// repeat that makes the MML too long.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::error::ConversionError;
+    /// use yks_converter_example::mml_converter::MmlConverter;
+    /// 
+    /// assert_eq!(MmlConverter::expand_repeats("t120[c[de]3]2f,[g]")?, "t120cdededecdededef,gg");
+    /// assert!(MmlConverter::expand_repeats("[cde,f]").is_err());
+    /// 
+    /// // Nesting multiplies: this would expand to about 9.5e9 notes
+    /// let error = MmlConverter::expand_repeats("[[[[[c]99]99]99]99]99").unwrap_err();
+    /// assert!(matches!(&error, ConversionError::InvalidMml(message) if message.contains("column 16")));
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn expand_repeats(mml: &str) -> Result<String, ConversionError> {
+        expand_repeat_brackets(mml).map_err(|(offset, message)| {
+            let (line, column) = line_column(mml, offset);
+            ConversionError::InvalidMml(format!("line {}, column {}: {}", line, column, message))
+        })
+    }
+
+    /// Returns `mml_text` with repeats expanded if enabled
+    fn expanded<'a>(&self, mml_text: &'a str) -> Result<Cow<'a, str>, ConversionError> {
+        if self.expand_repeats {
+            Ok(Cow::Owned(Self::expand_repeats(mml_text)?))
+        } else {
+            Ok(Cow::Borrowed(mml_text))
+        }
+    }
+
+    /// Sets the tempo used when the MML does not start with one
+    /// 
+    /// A tempo applies to the whole song, so `t<bpm>` is only prepended when
+    /// no part sets a tempo before its first note or rest; then it is added to
+    /// every part that plays something. Tempo changes later in the MML are
+    /// kept either way.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bpm` - Tempo in BPM (32-255)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the tempo is out of range.
+    pub fn set_default_tempo(&mut self, bpm: u16) -> Result<(), ConversionError> {
+        if !(32..=255).contains(&bpm) {
+            return Err(ConversionError::InvalidArgument(format!("Default tempo must be between 32 and 255, got {}", bpm)));
+        }
+        self.default_tempo = Some(bpm);
+        Ok(())
+    }
+
+    /// Sets the octave each part starts in when it does not choose one itself
+    /// 
+    /// `o<octave>` is prepended to every part that plays something and has no
+    /// `o` command before its first note or rest. Relative shifts (`<`, `>`)
+    /// before the first note count as not choosing an octave, so they move
+    /// from the default instead.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `octave` - Starting octave (0-8)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the octave is out of range.
+    pub fn set_default_octave(&mut self, octave: u8) -> Result<(), ConversionError> {
+        if octave > 8 {
+            return Err(ConversionError::InvalidArgument(format!("Default octave must be between 0 and 8, got {}", octave)));
+        }
+        self.default_octave = Some(octave);
+        Ok(())
+    }
+
+    /// Returns the normalized MML with the default tempo and octave prepended
+    /// where the parts lack them
+    /// 
+    /// This is the text conversion hands to yks_converter; see
+    /// [`MmlConverter::set_default_tempo`] and [`MmlConverter::set_default_octave`].
+    /// Without defaults it equals [`MmlConverter::normalize`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::mml_converter::MmlConverter;
+    /// 
+    /// let mut converter = MmlConverter::new();
+    /// converter.set_default_tempo(90)?;
+    /// converter.set_default_octave(5)?;
+    /// assert_eq!(converter.apply_defaults("MML@cde,o3ceg,;"), "t90o5cde,t90o3ceg,");
+    /// assert_eq!(converter.apply_defaults("t150cde,ceg"), "o5t150cde,o5ceg");
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn apply_defaults(&self, mml_text: &str) -> String {
+        let body = Self::normalize(mml_text);
+        if self.default_tempo.is_none() && self.default_octave.is_none() {
+            return body;
+        }
+
+        let parts: Vec<_> = mml_syntax::parts(&body)
+            .into_iter()
+            .map(|part| (part.text, part.tokens()))
+            .collect();
+        let lead_in = |tokens: &[mml_syntax::Token]| -> Vec<TokenKind> {
+            tokens.iter()
+                .map(|token| token.kind)
+                .take_while(|kind| !matches!(kind, TokenKind::Note { .. } | TokenKind::NoteNumber(_) | TokenKind::Rest { .. }))
+                .collect()
+        };
+        let plays = |tokens: &[mml_syntax::Token]| tokens.len() > lead_in(tokens).len();
+        let song_has_tempo = parts.iter()
+            .any(|(_, tokens)| lead_in(tokens).iter().any(|kind| matches!(kind, TokenKind::Tempo(Some(_)))));
+
+        parts.iter()
+            .map(|(text, tokens)| {
+                let mut prefix = String::new();
+                if plays(tokens) {
+                    if let Some(bpm) = self.default_tempo.filter(|_| !song_has_tempo) {
+                        prefix.push_str(&format!("t{}", bpm));
+                    }
+                    let has_octave = lead_in(tokens).iter().any(|kind| matches!(kind, TokenKind::Octave(Some(_))));
+                    if let Some(octave) = self.default_octave.filter(|_| !has_octave) {
+                        prefix.push_str(&format!("o{}", octave));
+                    }
+                }
+                prefix + text
+            })
+            .collect::<Vec<_>>()
+            .join(",")
     }
 
     /// Converts MML text to MIDI format
@@ -50,19 +424,202 @@ impl MmlConverter {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn convert_mml_to_midi(&self, mml_text: &str, output_path: &str) -> Result<(), String> {
-        let converter = YksConverter::new(mml_text.to_string(), self.instrument);
-        
-        let midi_data = converter.to_buffer()
-            .ok_or_else(|| "Failed to convert MML to MIDI buffer".to_string())?;
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_midi(&self, mml_text: &str, output_path: &str) -> Result<(), ConversionError> {
+        let midi_data = self.convert_mml_to_midi_bytes(mml_text)?;
         
         fs::write(output_path, midi_data.as_slice())
-            .map_err(|e| format!("Failed to write MIDI file: {}", e))?;
+            .map_err(|e| ConversionError::io("Failed to write MIDI file", e))?;
         
         Ok(())
     }
 
+    /// Converts MML text to in-memory MIDI data
+    /// 
+    /// Honors lenient mode (see [`MmlConverter::set_lenient`]). Warnings are
+    /// discarded; use [`MmlConverter::convert_mml_to_midi_reporting`] to get them.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with standard MIDI file contents, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_midi_bytes(&self, mml_text: &str) -> Result<Vec<u8>, ConversionError> {
+        Ok(self.convert_mml_to_midi_reporting(mml_text)?.midi_data)
+    }
+
+    /// Converts MML text to in-memory MIDI data, returning any warnings
+    /// 
+    /// Honors lenient mode (see [`MmlConverter::set_lenient`]); without it,
+    /// the only warning is about notes clamped by [`MmlConverter::set_transpose`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(LenientConversion)` with the MIDI data and warnings, or
+    /// `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_midi_reporting(&self, mml_text: &str) -> Result<LenientConversion, ConversionError> {
+        let mml_text = &*self.expanded(mml_text)?;
+        if self.lenient {
+            return self.convert_mml_to_midi_lenient(mml_text);
+        }
+
+        let midi_data = self.to_midi_buffer(mml_text).ok_or_else(|| {
+            let stopped_at = self.longest_convertible_prefix(mml_text).map_or(0, |(end, _)| end);
+            let (line, column) = line_column(mml_text, stopped_at);
+            ConversionError::InvalidMml(format!(
+                "Failed to convert MML to MIDI buffer (parsing stopped at line {}, column {})",
+                line, column
+            ))
+        })?;
+
+        let mut warnings = Vec::new();
+        let midi_data = self.post_process(midi_data, &mut warnings)?;
+        Ok(LenientConversion { midi_data, warnings })
+    }
+
+    /// Converts MML text to MIDI, keeping as much as possible on failure
+    /// 
+    /// If the full text cannot be converted, the longest convertible prefix is
+    /// used instead and a warning reports where parsing stopped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(LenientConversion)` with the (possibly partial) MIDI data, or
+    /// `Err(ConversionError)` if not even a prefix of the input could be converted.
+    pub fn convert_mml_to_midi_lenient(&self, mml_text: &str) -> Result<LenientConversion, ConversionError> {
+        let expanded = self.expanded(mml_text)?;
+        let mml_text = &*expanded;
+        let mut warnings = Vec::new();
+        if let Some(midi_data) = self.to_midi_buffer(mml_text) {
+            let midi_data = self.post_process(midi_data, &mut warnings)?;
+            return Ok(LenientConversion { midi_data, warnings });
+        }
+
+        let (stopped_at, midi_data) = self.longest_convertible_prefix(mml_text)
+            .ok_or_else(|| ConversionError::InvalidMml(
+                "Failed to convert MML to MIDI buffer: no part of the input could be converted".to_string()
+            ))?;
+        let (line, column) = line_column(mml_text, stopped_at);
+
+        warnings.push(format!(
+            "MML parsing stopped at line {}, column {}; output contains only the music before that point",
+            line, column
+        ));
+        let midi_data = self.post_process(midi_data, &mut warnings)?;
+        Ok(LenientConversion { midi_data, warnings })
+    }
+
+    /// Applies the configured channel layout, tempo scale and transposition to
+    /// generated MIDI, adding any warnings to `warnings`
+    fn post_process(&self, midi_data: Vec<u8>, warnings: &mut Vec<String>) -> Result<Vec<u8>, ConversionError> {
+        let midi_data = self.assign_voice_channels(midi_data)?;
+        if self.tempo_scale == 1.0 && self.transpose == 0 {
+            return Ok(midi_data);
+        }
+
+        let mut midi = MidiFile::parse(&midi_data)?;
+        midi.scale_tempo(self.tempo_scale)?;
+        warnings.extend(midi_file::clamped_notes_warning(midi.transpose(self.transpose), self.transpose));
+        Ok(midi.to_bytes())
+    }
+
+    /// Moves each voice to its own channel with its own program and volume, and
+    /// adds bank select messages, if configured
+    fn assign_voice_channels(&self, midi_data: Vec<u8>) -> Result<Vec<u8>, ConversionError> {
+        let reassign = !self.voice_instruments.is_empty() || !self.part_volumes.is_empty();
+        if !reassign && self.bank.is_none() {
+            return Ok(midi_data);
+        }
+
+        let mut midi = MidiFile::parse(&midi_data)?;
+        let mut voice = 0;
+        for track in &mut midi.tracks {
+            let Some(first_channel) = track.iter()
+                .find(|event| event.event.is_note_on())
+                .and_then(|event| event.event.channel_number()) else {
+                continue;
+            };
+
+            let (channel, program) = if reassign {
+                let program = self.voice_instruments.get(voice)
+                    .or(self.voice_instruments.last())
+                    .copied()
+                    .unwrap_or(self.instrument);
+                (voice_channel(voice), program)
+            } else {
+                (first_channel, self.instrument)
+            };
+            let mut has_program_change = false;
+            for event in track.iter_mut() {
+                if let Event::Channel { status, data } = &mut event.event {
+                    if reassign {
+                        *status = (*status & 0xf0) | channel;
+                    }
+                    if *status & 0xf0 == 0xc0 {
+                        if reassign {
+                            data[0] = program;
+                        }
+                        has_program_change = true;
+                    }
+                }
+            }
+            if !has_program_change {
+                track.insert(0, TrackEvent { delta: 0, event: Event::channel(0xc0, channel, &[program]) });
+            }
+            if let Some(bank) = self.bank {
+                insert_bank_select(track, bank);
+            }
+            if let Some(&volume) = self.part_volumes.get(voice).or(self.part_volumes.last()) {
+                track.insert(0, TrackEvent { delta: 0, event: Event::channel(0xb0, channel, &[7, volume]) });
+            }
+
+            voice += 1;
+        }
+
+        Ok(midi.to_bytes())
+    }
+
+    /// Runs yks_converter over the given MML text, always passing it in the
+    /// canonical `MML@...;` form with the configured defaults applied
+    fn to_midi_buffer(&self, mml_text: &str) -> Option<Vec<u8>> {
+        let mml = format!("{}{};", mml_syntax::MML_PREFIX, self.apply_defaults(mml_text));
+        YksConverter::new(mml, self.instrument).to_buffer()
+    }
+
+    /// Finds the longest prefix of `mml_text` that yks_converter accepts
+    /// 
+    /// The prefix is located by binary search over character boundaries.
+    /// 
+    /// Returns the prefix length in bytes together with its MIDI data.
+    fn longest_convertible_prefix(&self, mml_text: &str) -> Option<(usize, Vec<u8>)> {
+        let ends: Vec<usize> = mml_text.char_indices().map(|(i, c)| i + c.len_utf8()).collect();
+
+        let mut best = None;
+        let (mut low, mut high) = (0, ends.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.to_midi_buffer(&mml_text[..ends[mid]]) {
+                Some(midi_data) => {
+                    best = Some((ends[mid], midi_data));
+                    low = mid + 1;
+                }
+                None => high = mid,
+            }
+        }
+
+        best
+    }
+
     /// Converts MML file to MIDI file
     /// 
     /// # Arguments
@@ -72,7 +629,7 @@ impl MmlConverter {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
     /// 
     /// # Examples
     /// 
@@ -81,25 +638,74 @@ impl MmlConverter {
     /// 
     /// let converter = MmlConverter::new();
     /// converter.convert_mml_file_to_midi("song.mml", "output.mid")?;
-    /// # Ok::<(), String>(())
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
     /// ```
-    pub fn convert_mml_file_to_midi(&self, mml_file_path: &str, midi_file_path: &str) -> Result<(), String> {
+    pub fn convert_mml_file_to_midi(&self, mml_file_path: &str, midi_file_path: &str) -> Result<(), ConversionError> {
+        let midi_data = self.convert_mml_file_to_midi_bytes(mml_file_path)?;
+
+        fs::write(midi_file_path, midi_data.as_slice())
+            .map_err(|e| ConversionError::io("Failed to write MIDI file", e))?;
+
+        Ok(())
+    }
+
+    /// Converts an MML file to in-memory MIDI data
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to the input MML file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with standard MIDI file contents, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_file_to_midi_bytes(&self, mml_file_path: &str) -> Result<Vec<u8>, ConversionError> {
         // Check if MML file exists
         if !Path::new(mml_file_path).exists() {
-            return Err(format!("MML file not found: {}", mml_file_path));
+            return Err(ConversionError::not_found(format!("MML file not found: {}", mml_file_path)));
         }
 
         // Read MML file content
-        let mml_content = fs::read_to_string(mml_file_path)
-            .map_err(|e| format!("Failed to read MML file '{}': {}", mml_file_path, e))?;
+        let mml_content = read_mml_file(mml_file_path)
+            .map_err(|e| ConversionError::io(format!("Failed to read MML file '{}'", mml_file_path), e))?;
 
-        // Convert MML to MIDI
-        self.convert_mml_to_midi(&mml_content, midi_file_path)?;
+        self.convert_mml_to_midi_bytes(&mml_content)
+    }
 
-        Ok(())
+    /// Normalizes copied MML text into a bare, single-line body
+    /// 
+    /// Strips a leading `MML@` and a trailing `;`, trims surrounding whitespace
+    /// and removes line breaks inside the text. Part boundaries (`,`) are kept
+    /// exactly as they are. Conversion applies this automatically, so wrapped
+    /// and unwrapped MML produce identical MIDI.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml` - MML code as string
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::mml_converter::MmlConverter;
+    /// 
+    /// assert_eq!(MmlConverter::normalize("  MML@t120cde,\r\nceg,;\n"), "t120cde,ceg,");
+    /// assert_eq!(MmlConverter::normalize("t120cde,ceg,"), "t120cde,ceg,");
+    /// 
+    /// let converter = MmlConverter::new();
+    /// assert_eq!(
+    ///     converter.convert_mml_to_midi_bytes("MML@cde,ceg;")?,
+    ///     converter.convert_mml_to_midi_bytes("cde,ceg")?,
+    /// );
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn normalize(mml: &str) -> String {
+        let (_, body) = mml_syntax::strip_wrapper(mml);
+        body.chars().filter(|&c| c != '\n' && c != '\r').collect()
     }
 
-    /// Validates MML content before conversion
+    /// Reports the structure of MML content without converting it
+    /// 
+    /// Any `MML@...;` wrapper is ignored. The MML is validated first, so
+    /// malformed input is rejected the same way [`validate_mml`](Self::validate_mml) does.
     /// 
     /// # Arguments
     /// 
@@ -107,18 +713,155 @@ impl MmlConverter {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` if valid, or `Err(String)` with validation error.
-    pub fn validate_mml(&self, mml_text: &str) -> Result<(), String> {
-        if mml_text.trim().is_empty() {
-            return Err("MML content is empty".to_string());
+    /// Returns `Ok(MmlAnalysis)` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::mml_converter::MmlConverter;
+    /// 
+    /// let analysis = MmlConverter::new().analyze("MML@t150o5cde,c&c,r;")?;
+    /// assert_eq!(analysis.part_count(), 3);
+    /// assert_eq!(analysis.notes_per_part, vec![3, 1, 0]);
+    /// assert_eq!((analysis.tempo, analysis.default_octave), (150, 5));
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn analyze(&self, mml_text: &str) -> Result<MmlAnalysis, ConversionError> {
+        self.validate_mml(mml_text)?;
+
+        let mut tempo = None;
+        let mut default_octave = None;
+        let mut notes_per_part = Vec::new();
+
+        for part in mml_syntax::parts(mml_text) {
+            let mut octave = DEFAULT_OCTAVE;
+            let mut notes = 0;
+            let mut tied = false;
+
+            for token in part.tokens() {
+                match token.kind {
+                    TokenKind::Tempo(Some(value)) => {
+                        tempo.get_or_insert(value);
+                    }
+                    TokenKind::Octave(Some(value)) => octave = value,
+                    TokenKind::OctaveUp => octave += 1,
+                    TokenKind::OctaveDown => octave = octave.saturating_sub(1),
+                    TokenKind::Note { .. } | TokenKind::NoteNumber(_) => {
+                        if notes_per_part.is_empty() {
+                            default_octave.get_or_insert(octave);
+                        }
+                        if !tied {
+                            notes += 1;
+                        }
+                        tied = false;
+                    }
+                    TokenKind::Rest { .. } => tied = false,
+                    TokenKind::Tie => tied = true,
+                    _ => {}
+                }
+            }
+            notes_per_part.push(notes);
         }
 
-        // Basic MML syntax validation - check for common MML patterns
-        if !mml_text.chars().any(|c| "ABCDEFGRLTVabcdefgrltvN0123456789".contains(c)) {
-            return Err("Invalid MML format: no recognizable MML commands found".to_string());
+        Ok(MmlAnalysis {
+            notes_per_part,
+            tempo: tempo.unwrap_or(DEFAULT_TEMPO_BPM),
+            default_octave: default_octave.unwrap_or(DEFAULT_OCTAVE),
+        })
+    }
+
+    /// Converts MML text and checks the generated MIDI for stuck or silenced notes
+    /// 
+    /// yks_converter occasionally emits a note-on without its note-off, which
+    /// makes one note drone through the rest of the song. See
+    /// [`MidiFile::lint`] for the checks; the MIDI is generated with the
+    /// current settings, exactly as for a conversion.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<MidiLint>)`, empty if nothing suspicious was found, or
+    /// `Err(ConversionError)` if the MML cannot be converted.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::mml_converter::MmlConverter;
+    /// 
+    /// for lint in MmlConverter::new().lint_midi("MML@t120l4cdef;")? {
+    ///     eprintln!("⚠️  Warning: {}", lint);
+    /// }
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn lint_midi(&self, mml_text: &str) -> Result<Vec<MidiLint>, ConversionError> {
+        let midi_data = self.convert_mml_to_midi_bytes(mml_text)?;
+        Ok(MidiFile::parse(&midi_data)?.lint())
+    }
+
+    /// Validates MML content before conversion
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the first
+    /// problem and its line and column.
+    pub fn validate_mml(&self, mml_text: &str) -> Result<(), ConversionError> {
+        self.validate_mml_detailed(mml_text)
+            .map_err(|issues| ConversionError::InvalidMml(issues[0].to_string()))
+    }
+
+    /// Validates MML content and reports every problem found
+    /// 
+    /// Besides rejecting empty input, this checks for unknown characters, ties
+    /// (`&`) that do not join two notes, octaves outside 0-8, lengths outside
+    /// 1-64, tempos outside 32-255, volumes outside 0-15 and note numbers
+    /// outside 0-96. Issues are reported in source order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(Vec<MmlValidationError>)` listing all problems.
+    pub fn validate_mml_detailed(&self, mml_text: &str) -> Result<(), Vec<MmlValidationError>> {
+        let mut issues = Vec::new();
+
+        // Brackets are checked on their own; blanking them out keeps the
+        // positions of everything else for the remaining checks
+        let masked;
+        let mml_text = if self.expand_repeats {
+            if let Err((offset, message)) = expand_repeat_brackets(mml_text) {
+                let (line, column) = line_column(mml_text, offset);
+                issues.push(MmlValidationError { line, column, message });
+            }
+            masked = mask_repeat_brackets(mml_text);
+            &masked
+        } else {
+            mml_text
+        };
+
+        if mml_text.trim().is_empty() {
+            issues.push(MmlValidationError { line: 1, column: 1, message: "MML content is empty".to_string() });
+        } else if !mml_text.chars().any(|c| "ABCDEFGRLTVabcdefgrltvN0123456789".contains(c)) {
+            // Basic MML syntax validation - check for common MML patterns
+            issues.push(MmlValidationError {
+                line: 1,
+                column: 1,
+                message: "Invalid MML format: no recognizable MML commands found".to_string(),
+            });
+        } else {
+            issues.extend(syntax_errors(mml_text));
         }
+        issues.sort_by_key(|issue| (issue.line, issue.column));
 
-        Ok(())
+        if issues.is_empty() { Ok(()) } else { Err(issues) }
     }
 }
 
@@ -126,4 +869,250 @@ impl Default for MmlConverter {
     fn default() -> Self {
         Self::new()
     }
+}
+
+/// Decodes the contents of an MML file
+/// 
+/// Text starting with a byte order mark is decoded as UTF-16 LE, UTF-16 BE
+/// or UTF-8 accordingly, with the mark removed; some Mabinogi MML exports
+/// are UTF-16. Text without a mark must be UTF-8.
+/// 
+/// # Arguments
+/// 
+/// * `data` - Raw file contents
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(String)` with the MML text, or an `io::Error` of kind
+/// `InvalidData` if the text is not valid in its encoding.
+/// 
+/// # Example
+/// 
+/// ```
+/// use yks_converter_example::mml_converter::decode_mml_text;
+/// 
+/// let utf16_le: Vec<u8> = [0xfeff].into_iter().chain("MML@cde;".encode_utf16())
+///     .flat_map(u16::to_le_bytes)
+///     .collect();
+/// let utf16_be: Vec<u8> = [0xfeff].into_iter().chain("MML@cde;".encode_utf16())
+///     .flat_map(u16::to_be_bytes)
+///     .collect();
+/// 
+/// assert_eq!(decode_mml_text(&utf16_le)?, "MML@cde;");
+/// assert_eq!(decode_mml_text(&utf16_be)?, "MML@cde;");
+/// assert_eq!(decode_mml_text(b"\xef\xbb\xbfMML@cde;")?, "MML@cde;");
+/// assert_eq!(decode_mml_text(b"MML@cde;")?, "MML@cde;");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn decode_mml_text(data: &[u8]) -> io::Result<String> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let (utf16, big_endian) = match data {
+        [0xef, 0xbb, 0xbf, rest @ ..] => {
+            return String::from_utf8(rest.to_vec()).map_err(|_| invalid("MML text is not valid UTF-8"));
+        }
+        [0xff, 0xfe, rest @ ..] => (rest, false),
+        [0xfe, 0xff, rest @ ..] => (rest, true),
+        _ => return String::from_utf8(data.to_vec()).map_err(|_| invalid("MML text is not valid UTF-8")),
+    };
+
+    if !utf16.len().is_multiple_of(2) {
+        return Err(invalid("UTF-16 MML text ends partway through a character"));
+    }
+    let units = utf16.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if big_endian { u16::from_be_bytes(pair) } else { u16::from_le_bytes(pair) }
+    });
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| invalid("MML text is not valid UTF-16"))
+}
+
+/// Reads an MML file, decoding it as described in [`decode_mml_text`]
+/// 
+/// # Arguments
+/// 
+/// * `mml_file_path` - Path to the MML file
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(String)` with the MML text, or the `io::Error` from reading or decoding the file.
+pub fn read_mml_file(mml_file_path: impl AsRef<Path>) -> io::Result<String> {
+    decode_mml_text(&fs::read(mml_file_path)?)
+}
+
+/// Expands repeat brackets, see [`MmlConverter::expand_repeats`]
+/// 
+/// Errors carry the byte offset of the offending bracket.
+fn expand_repeat_brackets(mml: &str) -> Result<String, (usize, String)> {
+    let mut output = String::with_capacity(mml.len());
+    // Source offset of each open bracket and where its section starts in `output`
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut chars = mml.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '[' => open.push((offset, output.len())),
+            ']' => {
+                let Some((_, start)) = open.pop() else {
+                    return Err((offset, "Repeat ']' has no matching '['".to_string()));
+                };
+                let mut digits = String::new();
+                while let Some(&(_, digit)) = chars.peek() {
+                    if !digit.is_ascii_digit() {
+                        break;
+                    }
+                    digits.push(digit);
+                    chars.next();
+                }
+                let count = if digits.is_empty() {
+                    2
+                } else {
+                    digits.parse::<u32>()
+                        .ok()
+                        .filter(|count| (1..=MAX_REPEAT_COUNT).contains(count))
+                        .ok_or_else(|| (offset, format!("Repeat count {} is out of range (1-{})", digits, MAX_REPEAT_COUNT)))?
+                };
+                let section_len = output.len() - start;
+                let expanded_len = (count as usize - 1)
+                    .checked_mul(section_len)
+                    .and_then(|added| added.checked_add(output.len()));
+                if expanded_len.is_none_or(|len| len > MAX_EXPANDED_LEN) {
+                    return Err((offset, format!("Repeat expands the MML beyond {} bytes", MAX_EXPANDED_LEN)));
+                }
+                let section = output[start..].to_string();
+                for _ in 1..count {
+                    output.push_str(&section);
+                }
+            }
+            ',' if !open.is_empty() => {
+                return Err((open[open.len() - 1].0, "Repeat '[' is not closed before the end of its part".to_string()));
+            }
+            _ => output.push(c),
+        }
+    }
+
+    match open.last() {
+        Some(&(offset, _)) => Err((offset, "Repeat '[' is never closed".to_string())),
+        None => Ok(output),
+    }
+}
+
+/// Replaces repeat brackets and their counts with spaces, which MML parsing skips
+fn mask_repeat_brackets(mml: &str) -> String {
+    let mut masked = String::with_capacity(mml.len());
+    let mut in_count = false;
+    for c in mml.chars() {
+        in_count = match c {
+            '[' => false,
+            ']' => true,
+            c if c.is_ascii_digit() => in_count,
+            _ => false,
+        };
+        masked.push(if c == '[' || in_count { ' ' } else { c });
+    }
+    masked
+}
+
+/// Converts a byte offset in `text` to a 1-based (line, column) pair
+fn line_column(text: &str, byte_offset: usize) -> (usize, usize) {
+    let before = &text[..byte_offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+/// Scans every part of `mml_text` for malformed commands
+fn syntax_errors(mml_text: &str) -> Vec<MmlValidationError> {
+    let mut errors = Vec::new();
+    let mut report = |offset: usize, message: String| {
+        let (line, column) = line_column(mml_text, offset);
+        errors.push(MmlValidationError { line, column, message });
+    };
+
+    for part in mml_syntax::parts(mml_text) {
+        let tokens = part.tokens();
+        let mut octave = DEFAULT_OCTAVE as i64;
+
+        for (i, token) in tokens.iter().enumerate() {
+            match token.kind {
+                TokenKind::Unknown(c) => report(token.offset, format!("Unexpected character '{}'", c)),
+                TokenKind::Octave(None) => report(token.offset, "Octave command 'o' is missing a value".to_string()),
+                TokenKind::Octave(Some(value)) => {
+                    if value > 8 {
+                        report(token.offset, format!("Octave {} is out of range (0-8)", value));
+                    }
+                    octave = value as i64;
+                }
+                TokenKind::OctaveUp | TokenKind::OctaveDown => {
+                    octave += if token.kind == TokenKind::OctaveUp { 1 } else { -1 };
+                    if !(0..=8).contains(&octave) {
+                        report(token.offset, format!("Octave shift moves to octave {}, outside 0-8", octave));
+                        octave = octave.clamp(0, 8);
+                    }
+                }
+                TokenKind::Note { length: Some(length), .. }
+                | TokenKind::Rest { length: Some(length), .. }
+                | TokenKind::Length { length: Some(length), .. }
+                    if !(1..=64).contains(&length) =>
+                {
+                    report(token.offset, format!("Length {} is out of range (1-64)", length));
+                }
+                TokenKind::Length { length: None, .. } => {
+                    report(token.offset, "Length command 'l' is missing a value".to_string())
+                }
+                TokenKind::Tempo(value) => match value {
+                    Some(32..=255) => {}
+                    Some(value) => report(token.offset, format!("Tempo {} is out of range (32-255)", value)),
+                    None => report(token.offset, "Tempo command 't' is missing a value".to_string()),
+                },
+                TokenKind::Volume(value) => match value {
+                    Some(0..=15) => {}
+                    Some(value) => report(token.offset, format!("Volume {} is out of range (0-15)", value)),
+                    None => report(token.offset, "Volume command 'v' is missing a value".to_string()),
+                },
+                TokenKind::NoteNumber(value) => match value {
+                    Some(0..=96) => {}
+                    Some(value) => report(token.offset, format!("Note number {} is out of range (0-96)", value)),
+                    None => report(token.offset, "Note command 'n' is missing a value".to_string()),
+                },
+                TokenKind::Tie => {
+                    let joins = |token: Option<&mml_syntax::Token>| {
+                        matches!(token.map(|t| t.kind), Some(TokenKind::Note { .. } | TokenKind::NoteNumber(_) | TokenKind::Rest { .. }))
+                    };
+                    let before = i.checked_sub(1).and_then(|j| tokens.get(j));
+                    // Octave shifts may sit between the tied notes, e.g. `b&>c`
+                    let after = tokens[i + 1..]
+                        .iter()
+                        .find(|t| !matches!(t.kind, TokenKind::OctaveUp | TokenKind::OctaveDown | TokenKind::Octave(_)));
+                    if !joins(before) || !joins(after) {
+                        report(token.offset, "Tie '&' must join two notes".to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    errors
+}
+
+/// Inserts bank select MSB/LSB (CC 0 and CC 32) in front of every program change
+fn insert_bank_select(track: &mut Vec<TrackEvent>, bank: u16) {
+    let events = std::mem::take(track);
+    for event in events {
+        if let (Some(0xc0), Some(channel)) = (event.event.message_type(), event.event.channel_number()) {
+            track.push(TrackEvent { delta: event.delta, event: Event::channel(0xb0, channel, &[0, (bank >> 7) as u8]) });
+            track.push(TrackEvent { delta: 0, event: Event::channel(0xb0, channel, &[32, (bank & 0x7f) as u8]) });
+            track.push(TrackEvent { delta: 0, event: event.event });
+        } else {
+            track.push(event);
+        }
+    }
+}
+
+/// Maps a voice index to a MIDI channel, skipping the GM drum channel 9
+fn voice_channel(voice: usize) -> u8 {
+    let channel = (voice % 15) as u8;
+    if channel >= 9 { channel + 1 } else { channel }
 }
\ No newline at end of file
@@ -0,0 +1,172 @@
+/*!
+ * MML Syntax Module
+ *
+ * Lightweight tokenizer for Mabinogi MML. yks_converter only produces MIDI and
+ * reports nothing about the structure of its input, so validation and analysis
+ * scan the text themselves with this module.
+ */
+
+/// Prefix used by Mabinogi MML exports, e.g. `MML@t120cde,ceg;`
+pub const MML_PREFIX: &str = "MML@";
+
+/// Octave a part starts in before any `o`, `<` or `>` command
+pub const DEFAULT_OCTAVE: u32 = 4;
+
+/// Tempo in BPM used until a `t` command appears
+pub const DEFAULT_TEMPO_BPM: u32 = 120;
+
+/// A single MML command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Note `a`-`g`; `accidental` is +1 for `+`/`#`, -1 for `-`
+    Note { letter: char, accidental: i8, length: Option<u32>, dots: u32 },
+    /// Rest `r`
+    Rest { length: Option<u32>, dots: u32 },
+    /// Absolute note number `n`
+    NoteNumber(Option<u32>),
+    /// Octave `o`
+    Octave(Option<u32>),
+    /// Octave up `>`
+    OctaveUp,
+    /// Octave down `<`
+    OctaveDown,
+    /// Default length `l`
+    Length { length: Option<u32>, dots: u32 },
+    /// Tempo `t`
+    Tempo(Option<u32>),
+    /// Volume `v`
+    Volume(Option<u32>),
+    /// Tie `&`
+    Tie,
+    /// Character that is not an MML command
+    Unknown(char),
+}
+
+/// A command together with its byte offset in the full MML text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// What the command is
+    pub kind: TokenKind,
+    /// Byte offset of the command letter in the full text
+    pub offset: usize,
+}
+
+/// A comma-separated part (voice) of an MML text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part<'a> {
+    /// Byte offset of the part in the full text
+    pub offset: usize,
+    /// Source of the part
+    pub text: &'a str,
+}
+
+impl<'a> Part<'a> {
+    /// Tokenizes the part
+    pub fn tokens(&self) -> Vec<Token> {
+        tokenize(self.text, self.offset)
+    }
+}
+
+/// Returns the MML body with surrounding whitespace, a leading `MML@` and a
+/// trailing `;` removed, together with its byte offset in `text`
+pub fn strip_wrapper(text: &str) -> (usize, &str) {
+    let mut offset = text.len() - text.trim_start().len();
+    let mut body = text.trim();
+
+    if body.len() >= MML_PREFIX.len() && body[..MML_PREFIX.len()].eq_ignore_ascii_case(MML_PREFIX) {
+        body = &body[MML_PREFIX.len()..];
+        offset += MML_PREFIX.len();
+    }
+    body = body.strip_suffix(';').unwrap_or(body);
+
+    (offset, body)
+}
+
+/// Splits MML text into its comma-separated parts, ignoring any `MML@...;` wrapper
+pub fn parts(text: &str) -> Vec<Part<'_>> {
+    let (mut offset, body) = strip_wrapper(text);
+    body.split(',')
+        .map(|part| {
+            let current = Part { offset, text: part };
+            offset += part.len() + 1;
+            current
+        })
+        .collect()
+}
+
+/// Tokenizes one part of MML; offsets are shifted by `base_offset`
+pub fn tokenize(text: &str, base_offset: usize) -> Vec<Token> {
+    let mut scanner = Scanner { text, pos: 0 };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = scanner.next_char() {
+        let offset = base_offset + scanner.pos - c.len_utf8();
+        let kind = match c.to_ascii_lowercase() {
+            c if c.is_whitespace() => continue,
+            letter @ 'a'..='g' => {
+                let accidental = match scanner.peek() {
+                    Some('+') | Some('#') => { scanner.pos += 1; 1 }
+                    Some('-') => { scanner.pos += 1; -1 }
+                    _ => 0,
+                };
+                let length = scanner.number();
+                TokenKind::Note { letter, accidental, length, dots: scanner.dots() }
+            }
+            'r' => {
+                let length = scanner.number();
+                TokenKind::Rest { length, dots: scanner.dots() }
+            }
+            'l' => {
+                let length = scanner.number();
+                TokenKind::Length { length, dots: scanner.dots() }
+            }
+            'n' => TokenKind::NoteNumber(scanner.number()),
+            'o' => TokenKind::Octave(scanner.number()),
+            't' => TokenKind::Tempo(scanner.number()),
+            'v' => TokenKind::Volume(scanner.number()),
+            '>' => TokenKind::OctaveUp,
+            '<' => TokenKind::OctaveDown,
+            '&' => TokenKind::Tie,
+            _ => TokenKind::Unknown(c),
+        };
+        tokens.push(Token { kind, offset });
+    }
+
+    tokens
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Reads a decimal number, saturating instead of overflowing
+    fn number(&mut self) -> Option<u32> {
+        let digits = self.text[self.pos..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let value = self.text[self.pos..self.pos + digits]
+            .bytes()
+            .fold(0u32, |acc, digit| acc.saturating_mul(10).saturating_add((digit - b'0') as u32));
+        self.pos += digits;
+        Some(value)
+    }
+
+    fn dots(&mut self) -> u32 {
+        let dots = self.text[self.pos..].bytes().take_while(|&b| b == b'.').count();
+        self.pos += dots;
+        dots as u32
+    }
+}
\ No newline at end of file
@@ -5,10 +5,302 @@
  * Supports both mono and stereo WAV files with optimal quality settings.
  */
 
-use crate::lame_bindings::LameEncoder;
+use crate::audio_utils;
+use crate::dither;
+use crate::error::ConversionError;
+use crate::id3::Id3Tag;
+use crate::lame_bindings::{self, LameEncoder, StereoMode};
 use hound::{WavReader, SampleFormat};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufReader, BufWriter, Read, Write};
+
+/// Default MP3 bitrate in kbps
+pub const DEFAULT_BITRATE: u32 = 192;
+
+/// CBR bitrates (kbps) accepted by [`Mp3Encoder::convert_wav_to_mp3_with_bitrate`]
+/// 
+/// Not every bitrate is legal at every sample rate, see [`supported_bitrates_at`].
+pub const SUPPORTED_BITRATES: [u32; 10] = [8, 16, 32, 64, 96, 128, 160, 192, 256, 320];
+
+/// Supported bitrates (kbps) that MPEG-1 streams (32, 44.1 and 48 kHz) can carry
+const MPEG1_BITRATES: [u32; 8] = [32, 64, 96, 128, 160, 192, 256, 320];
+
+/// Supported bitrates (kbps) that MPEG-2 and MPEG-2.5 streams (8 to 24 kHz) can carry
+const MPEG2_BITRATES: [u32; 7] = [8, 16, 32, 64, 96, 128, 160];
+
+/// Returns the entries of [`SUPPORTED_BITRATES`] LAME can encode at `sample_rate`
+/// 
+/// MPEG-1 rates (32 kHz and up) start at 32 kbps; the lower MPEG-2 and
+/// MPEG-2.5 rates top out at 160 kbps.
+/// 
+/// # Example
+/// 
+/// ```
+/// use yks_converter_example::mp3_encoder::supported_bitrates_at;
+/// 
+/// assert!(!supported_bitrates_at(44100).contains(&16));
+/// assert!(!supported_bitrates_at(22050).contains(&320));
+/// ```
+pub fn supported_bitrates_at(sample_rate: u32) -> &'static [u32] {
+    if sample_rate >= 32000 { &MPEG1_BITRATES } else { &MPEG2_BITRATES }
+}
+
+/// Largest gain (in dB) peak normalization will apply
+pub const MAX_NORMALIZE_GAIN_DB: f64 = 24.0;
+
+/// Peaks below this level (in dBFS) are treated as silence and never amplified
+pub const SILENCE_THRESHOLD_DBFS: f64 = -60.0;
+
+/// Ceiling (in dBFS) the safety limiter of loudness normalization keeps peaks under
+pub const LOUDNORM_CEILING_DBFS: f64 = -1.0;
+
+/// Level adjustment applied to the samples before encoding
+/// 
+/// Peak and loudness normalization are mutually exclusive: both set the
+/// overall gain, so at most one of them can be in effect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+    /// Scale so the highest peak hits this level in dBFS, see [`Mp3Encoder::normalize_peak`]
+    Peak(f64),
+    /// Scale so the integrated loudness hits this level in LUFS, see [`Mp3Encoder::normalize_loudness`]
+    Loudness(f64),
+}
+
+impl Normalization {
+    /// Checks that the target is in range for its kind
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the problem.
+    pub fn validate(self) -> Result<(), ConversionError> {
+        match self {
+            Normalization::Peak(target_peak_dbfs) => Mp3Encoder::validate_target_peak(target_peak_dbfs),
+            Normalization::Loudness(target_lufs) => Mp3Encoder::validate_target_lufs(target_lufs),
+        }
+    }
+
+    /// Applies the normalization to interleaved samples in place
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(gain)` with the linear gain that was applied, or `Err(ConversionError)`
+    /// if the target is invalid.
+    pub fn apply(self, samples: &mut [i16], channels: u16, sample_rate: u32) -> Result<f64, ConversionError> {
+        match self {
+            Normalization::Peak(target_peak_dbfs) => Mp3Encoder::normalize_peak(samples, target_peak_dbfs),
+            Normalization::Loudness(target_lufs) => Mp3Encoder::normalize_loudness(samples, channels, sample_rate, target_lufs),
+        }
+    }
+}
+
+/// Encoding options and ID3 metadata for [`Mp3Encoder::convert_wav_to_mp3_with_config`]
+/// 
+/// Metadata fields that are set are written as an ID3v2.3 tag in front of the
+/// audio data, so players show them instead of "Unknown Artist". Text that is
+/// not representable in ISO-8859-1 is stored as UTF-16.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use yks_converter_example::mp3_encoder::{Mp3Encoder, Mp3EncoderConfig};
+/// 
+/// let config = Mp3EncoderConfig {
+///     title: Some("Song of the Road".to_string()),
+///     artist: Some("Bard".to_string()),
+///     ..Mp3EncoderConfig::default()
+/// };
+/// Mp3Encoder::convert_wav_to_mp3_with_config("song.wav", "song.mp3", &config)?;
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mp3EncoderConfig {
+    /// Bitrate in kbps, one of [`SUPPORTED_BITRATES`]
+    pub bitrate: u32,
+    /// LAME algorithm quality (0-9, 0 is best and slowest)
+    pub quality: u8,
+    /// Channel mode, or `None` to let LAME choose (joint stereo for most stereo input)
+    pub stereo_mode: Option<StereoMode>,
+    /// MP3 sample rate, one of [`lame_bindings::SUPPORTED_OUT_SAMPLE_RATES`],
+    /// or `None` to keep the input rate; LAME resamples while encoding
+    pub out_sample_rate: Option<u32>,
+    /// Average stereo input into a single channel and write a mono MP3,
+    /// overriding `stereo_mode`; a mono MP3 keeps its quality at about half
+    /// the bitrate, so pair this with a lower `bitrate` for smaller files
+    pub force_mono: bool,
+    /// Mix WAV input with 3 to 8 channels down to stereo before encoding, see
+    /// [`audio_utils::downmix_to_stereo`]; without it such input is rejected
+    pub downmix_multichannel: bool,
+    /// Apply TPDF dither when reducing 24/32-bit or float WAV input to 16-bit;
+    /// disable for bit-exact output
+    pub dither: bool,
+    /// Add a CRC-16 checksum to every frame, see [`LameEncoder::set_error_protection`];
+    /// costs about 0.6 kbps of audio data at 44.1 kHz, the file size is unchanged
+    pub error_protection: bool,
+    /// Make every frame self-contained for streaming decoders, see
+    /// [`LameEncoder::set_disable_reservoir`]; slightly lowers quality at the same bitrate
+    pub disable_reservoir: bool,
+    /// Track title (`TIT2`)
+    pub title: Option<String>,
+    /// Artist (`TPE1`)
+    pub artist: Option<String>,
+    /// Album (`TALB`)
+    pub album: Option<String>,
+    /// Release year (`TYER`)
+    pub year: Option<u16>,
+}
+
+impl Default for Mp3EncoderConfig {
+    fn default() -> Self {
+        Mp3EncoderConfig {
+            bitrate: DEFAULT_BITRATE,
+            quality: lame_bindings::DEFAULT_QUALITY,
+            stereo_mode: None,
+            out_sample_rate: None,
+            force_mono: false,
+            downmix_multichannel: false,
+            dither: true,
+            error_protection: false,
+            disable_reservoir: false,
+            title: None,
+            artist: None,
+            album: None,
+            year: None,
+        }
+    }
+}
+
+impl Mp3EncoderConfig {
+    /// Returns the default configuration with the given bitrate
+    pub fn with_bitrate(bitrate: u32) -> Self {
+        Mp3EncoderConfig { bitrate, ..Mp3EncoderConfig::default() }
+    }
+
+    /// Builds the ID3 tag holding the configured metadata
+    /// 
+    /// The tag is empty when no metadata is set.
+    pub fn to_id3_tag(&self) -> Id3Tag {
+        let mut tag = Id3Tag::new();
+        if let Some(title) = &self.title {
+            tag.add_text(*b"TIT2", title);
+        }
+        if let Some(artist) = &self.artist {
+            tag.add_text(*b"TPE1", artist);
+        }
+        if let Some(album) = &self.album {
+            tag.add_text(*b"TALB", album);
+        }
+        if let Some(year) = self.year {
+            tag.add_text(*b"TYER", &format!("{:04}", year));
+        }
+        tag
+    }
+
+    /// Checks the bitrate and metadata
+    /// 
+    /// With `out_sample_rate` set, the bitrate must also be legal at that
+    /// rate; otherwise it depends on the input, see
+    /// [`Mp3EncoderConfig::validate_for_input_rate`].
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the problem.
+    pub fn validate(&self) -> Result<(), ConversionError> {
+        Mp3Encoder::validate_bitrate(self.bitrate)?;
+        if self.quality > 9 {
+            return Err(ConversionError::InvalidArgument(format!("Encode quality must be between 0 and 9, got {}", self.quality)));
+        }
+        if let Some(out_sample_rate) = self.out_sample_rate {
+            lame_bindings::validate_out_sample_rate(out_sample_rate)?;
+            Mp3Encoder::validate_bitrate_at(self.bitrate, out_sample_rate)?;
+        }
+        if let Some(year) = self.year.filter(|&year| year > 9999) {
+            return Err(ConversionError::InvalidArgument(format!("Year must have at most four digits, got {}", year)));
+        }
+        Ok(())
+    }
+
+    /// Checks the configuration for encoding audio at `input_sample_rate`
+    /// 
+    /// Like [`Mp3EncoderConfig::validate`]. When `out_sample_rate` is not set,
+    /// the input rate becomes the MP3 rate, so it must be a legal MP3 sample
+    /// rate and the bitrate is checked against it. Either problem is thus
+    /// reported before any audio is synthesized.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input_sample_rate` - Sample rate of the audio that will be encoded, in Hz
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the problem.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::mp3_encoder::Mp3EncoderConfig;
+    /// 
+    /// assert!(Mp3EncoderConfig::with_bitrate(16).validate_for_input_rate(44100).is_err());
+    /// assert!(Mp3EncoderConfig::with_bitrate(16).validate_for_input_rate(22050).is_ok());
+    /// let resampled = Mp3EncoderConfig { out_sample_rate: Some(22050), ..Mp3EncoderConfig::with_bitrate(320) };
+    /// assert!(resampled.validate_for_input_rate(44100).is_err());
+    /// assert!(Mp3EncoderConfig::default().validate_for_input_rate(96000).is_err());
+    /// let downsampled = Mp3EncoderConfig { out_sample_rate: Some(48000), ..Mp3EncoderConfig::default() };
+    /// assert!(downsampled.validate_for_input_rate(96000).is_ok());
+    /// ```
+    pub fn validate_for_input_rate(&self, input_sample_rate: u32) -> Result<(), ConversionError> {
+        self.validate()?;
+        let sample_rate = match self.out_sample_rate {
+            Some(out_sample_rate) => out_sample_rate,
+            None => {
+                lame_bindings::validate_out_sample_rate(input_sample_rate).map_err(|_| {
+                    ConversionError::InvalidArgument(format!(
+                        "{} Hz is not a valid MP3 sample rate; set an output sample rate, one of {:?}",
+                        input_sample_rate,
+                        lame_bindings::SUPPORTED_OUT_SAMPLE_RATES
+                    ))
+                })?;
+                input_sample_rate
+            }
+        };
+        Mp3Encoder::validate_bitrate_at(self.bitrate, sample_rate)
+    }
+}
+
+/// Size and length of an encoded MP3 stream, see [`Mp3Encoder::convert_wav_to_mp3_reporting`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mp3Result {
+    /// Bytes written to the output, including any ID3 tag
+    pub bytes_written: u64,
+    /// Number of PCM frames (samples per channel) fed to LAME
+    pub frames_encoded: usize,
+    /// Sample rate of the PCM input in Hz
+    pub sample_rate: u32,
+}
+
+impl Mp3Result {
+    /// Returns the playing time of the encoded audio in seconds
+    pub fn duration_secs(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.frames_encoded as f64 / self.sample_rate as f64
+    }
+}
+
+/// PCM audio as handed to LAME, after normalization and before any channel downmix
+pub(crate) struct EncodedAudio {
+    pub samples: Vec<i16>,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub output: Mp3Result,
+}
+
+impl EncodedAudio {
+    /// Integrated loudness in LUFS, see [`audio_utils::measure_lufs`]
+    pub fn loudness_lufs(&self) -> f64 {
+        audio_utils::measure_lufs(&self.samples, self.channels, self.sample_rate)
+    }
+}
 
 /// High-quality MP3 encoder using LAME
 /// 
@@ -21,110 +313,798 @@ impl Mp3Encoder {
     /// 
     /// # Arguments
     /// 
-    /// * `wav_path` - Path to the input WAV file (16-bit, mono or stereo)
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
     /// * `mp3_path` - Path for the output MP3 file
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
     /// 
     /// # Quality Settings
     /// 
-    /// - 192 kbps bitrate for high quality
-    /// - Highest quality setting (quality=0)
+    /// - 192 kbps bitrate for high quality (see [`Mp3Encoder::convert_wav_to_mp3_with_bitrate`])
+    /// - Highest quality setting (quality=0, see [`Mp3EncoderConfig::quality`])
     /// - Supports both mono and stereo input
+    /// - 24/32-bit integer and 32-bit float input is dithered down to 16-bit for LAME
     /// - 1152 sample frame processing for optimal compression
-    pub fn convert_wav_to_mp3(wav_path: &str, mp3_path: &str) -> Result<(), String> {
-        let mut reader = WavReader::open(wav_path)
-            .map_err(|e| format!("Failed to open WAV file: {}", e))?;
-        
+    pub fn convert_wav_to_mp3(wav_path: &str, mp3_path: &str) -> Result<(), ConversionError> {
+        Self::convert_wav_to_mp3_reporting(wav_path, mp3_path)?;
+        Ok(())
+    }
+
+    /// Converts a WAV file to MP3 like [`Mp3Encoder::convert_wav_to_mp3`] and
+    /// reports the size and length of the result
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Mp3Result)` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::mp3_encoder::Mp3Encoder;
+    /// 
+    /// let result = Mp3Encoder::convert_wav_to_mp3_reporting("input.wav", "output.mp3")?;
+    /// let wav_bytes = std::fs::metadata("input.wav")?.len();
+    /// println!(
+    ///     "{:.1} s, compressed {:.1}:1",
+    ///     result.duration_secs(),
+    ///     wav_bytes as f64 / result.bytes_written as f64
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn convert_wav_to_mp3_reporting(wav_path: &str, mp3_path: &str) -> Result<Mp3Result, ConversionError> {
+        let encoded = Self::encode(wav_path, mp3_path, None, &Mp3EncoderConfig::default(), None)?;
+        Ok(encoded.output)
+    }
+
+    /// Converts a WAV file to MP3 format at the given constant bitrate
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// * `bitrate` - Bitrate in kbps, one of [`SUPPORTED_BITRATES`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the bitrate is not supported
+    /// or encoding fails.
+    pub fn convert_wav_to_mp3_with_bitrate(wav_path: &str, mp3_path: &str, bitrate: u32) -> Result<(), ConversionError> {
+        Self::encode(wav_path, mp3_path, None, &Mp3EncoderConfig::with_bitrate(bitrate), None)?;
+        Ok(())
+    }
+
+    /// Converts a WAV file to MP3 using the bitrate and metadata in `config`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// * `config` - Bitrate and ID3 metadata
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_wav_to_mp3_with_config(wav_path: &str, mp3_path: &str, config: &Mp3EncoderConfig) -> Result<(), ConversionError> {
+        let tag = config.to_id3_tag();
+        Self::encode(wav_path, mp3_path, Some(&tag).filter(|tag| !tag.is_empty()), config, None)?;
+        Ok(())
+    }
+
+    /// Converts a WAV file to MP3, scaling the audio so its peak hits a target level
+    /// 
+    /// The samples are buffered in memory: a first pass finds the peak sample,
+    /// and a second pass applies the gain (saturating at full scale) before the
+    /// data is fed to LAME. Input that is effectively silent (peak below
+    /// [`SILENCE_THRESHOLD_DBFS`]) is left untouched, and the gain is capped at
+    /// [`MAX_NORMALIZE_GAIN_DB`] so quiet noise is not blown up.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// * `target_peak_dbfs` - Desired peak level in dBFS (at most 0.0), e.g. -1.0
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_wav_to_mp3_normalized(wav_path: &str, mp3_path: &str, target_peak_dbfs: f64) -> Result<(), ConversionError> {
+        Self::encode(wav_path, mp3_path, None, &Mp3EncoderConfig::default(), Some(Normalization::Peak(target_peak_dbfs)))?;
+        Ok(())
+    }
+
+    /// Converts a WAV file to MP3, scaling the audio to a target integrated loudness
+    /// 
+    /// Two passes over the buffered samples: the first measures the loudness
+    /// with [`crate::audio_utils::measure_lufs`], the second applies the gain
+    /// needed to reach `target_lufs` through the look-ahead limiter of
+    /// [`crate::audio_utils::limit`]. Samples that stay under
+    /// [`LOUDNORM_CEILING_DBFS`] after the gain pass through unchanged; around
+    /// peaks that would exceed it, the gain is ramped down briefly instead of
+    /// clipping, which lowers the loudness reached slightly for very dynamic
+    /// material. Silent input is left untouched and the gain is capped at
+    /// [`MAX_NORMALIZE_GAIN_DB`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// * `target_lufs` - Desired integrated loudness in LUFS (-60.0 to 0.0), e.g. -14.0
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_wav_to_mp3_loudnorm(wav_path: &str, mp3_path: &str, target_lufs: f64) -> Result<(), ConversionError> {
+        Self::encode(wav_path, mp3_path, None, &Mp3EncoderConfig::default(), Some(Normalization::Loudness(target_lufs)))?;
+        Ok(())
+    }
+
+    /// Scales samples in place so the peak reaches `target_peak_dbfs`
+    /// 
+    /// See [`Mp3Encoder::convert_wav_to_mp3_normalized`] for how silence and
+    /// the gain limit are handled.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - 16-bit PCM samples
+    /// * `target_peak_dbfs` - Desired peak level in dBFS (at most 0.0)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(gain)` with the linear gain that was applied, or `Err(ConversionError)`
+    /// if the target is above 0 dBFS.
+    pub fn normalize_peak(samples: &mut [i16], target_peak_dbfs: f64) -> Result<f64, ConversionError> {
+        Self::validate_target_peak(target_peak_dbfs)?;
+
+        let peak = samples.iter().map(|sample| sample.unsigned_abs()).max().unwrap_or(0) as f64 / i16::MAX as f64;
+        if peak == 0.0 || 20.0 * peak.log10() < SILENCE_THRESHOLD_DBFS {
+            return Ok(1.0);
+        }
+
+        let target = 10f64.powf(target_peak_dbfs / 20.0);
+        let gain = (target / peak).min(10f64.powf(MAX_NORMALIZE_GAIN_DB / 20.0));
+        for sample in samples.iter_mut() {
+            *sample = (*sample as f64 * gain).round().clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+        }
+
+        Ok(gain)
+    }
+
+    /// Scales interleaved samples in place to reach `target_lufs`
+    /// 
+    /// See [`Mp3Encoder::convert_wav_to_mp3_loudnorm`] for how peaks, silence
+    /// and the gain limit are handled.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - Interleaved 16-bit PCM samples
+    /// * `channels` - Number of interleaved channels
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `target_lufs` - Desired integrated loudness in LUFS (-60.0 to 0.0)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(gain)` with the linear gain that was applied, or `Err(ConversionError)`
+    /// if the target is out of range.
+    pub fn normalize_loudness(samples: &mut [i16], channels: u16, sample_rate: u32, target_lufs: f64) -> Result<f64, ConversionError> {
+        Self::validate_target_lufs(target_lufs)?;
+
+        let loudness = audio_utils::measure_lufs(samples, channels, sample_rate);
+        if loudness < SILENCE_THRESHOLD_DBFS {
+            return Ok(1.0);
+        }
+
+        let gain = 10f64.powf(((target_lufs - loudness) / 20.0).min(MAX_NORMALIZE_GAIN_DB / 20.0));
+        // Gain is applied in f64 so the limiter sees peaks above full scale
+        // before anything has clipped
+        let mut levels: Vec<f64> = samples.iter().map(|&sample| sample as f64 * gain).collect();
+        let ceiling = audio_utils::limit_levels(&mut levels, LOUDNORM_CEILING_DBFS, audio_utils::LimiterSettings::default());
+        for (sample, level) in samples.iter_mut().zip(levels) {
+            *sample = level.round().clamp(-ceiling, ceiling) as i16;
+        }
+
+        Ok(gain)
+    }
+
+    /// Checks that a loudness normalization target is between -60 and 0 LUFS
+    /// 
+    /// # Arguments
+    /// 
+    /// * `target_lufs` - Integrated loudness in LUFS
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the problem.
+    pub fn validate_target_lufs(target_lufs: f64) -> Result<(), ConversionError> {
+        if (-60.0..=0.0).contains(&target_lufs) {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidArgument(format!(
+                "Target loudness must be between -60 and 0 LUFS, got {}",
+                target_lufs
+            )))
+        }
+    }
+
+    /// Checks that a peak normalization target is a finite level at or below 0 dBFS
+    /// 
+    /// # Arguments
+    /// 
+    /// * `target_peak_dbfs` - Peak level in dBFS
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the problem.
+    pub fn validate_target_peak(target_peak_dbfs: f64) -> Result<(), ConversionError> {
+        if target_peak_dbfs.is_finite() && target_peak_dbfs <= 0.0 {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidArgument(format!(
+                "Target peak must be at most 0 dBFS, got {}",
+                target_peak_dbfs
+            )))
+        }
+    }
+
+    /// Checks that `bitrate` is one of LAME's accepted CBR values
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bitrate` - Bitrate in kbps
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if supported, or `Err(ConversionError)` listing the accepted values.
+    pub fn validate_bitrate(bitrate: u32) -> Result<(), ConversionError> {
+        if SUPPORTED_BITRATES.contains(&bitrate) {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidArgument(format!(
+                "Unsupported MP3 bitrate: {} kbps (supported: {:?})",
+                bitrate, SUPPORTED_BITRATES
+            )))
+        }
+    }
+
+    /// Checks that `bitrate` is supported and legal at `sample_rate`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bitrate` - Bitrate in kbps
+    /// * `sample_rate` - MP3 sample rate in Hz
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` listing the bitrates
+    /// accepted at that rate.
+    pub fn validate_bitrate_at(bitrate: u32, sample_rate: u32) -> Result<(), ConversionError> {
+        Self::validate_bitrate(bitrate)?;
+        let legal = supported_bitrates_at(sample_rate);
+        if legal.contains(&bitrate) {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidArgument(format!(
+                "{} kbps is not a valid MP3 bitrate at {} Hz (supported at that rate: {:?})",
+                bitrate, sample_rate, legal
+            )))
+        }
+    }
+
+    /// Converts a WAV file to MP3 format, prepending an ID3v2 tag
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// * `tag` - ID3v2 tag written before the audio data
+    /// * `bitrate` - Bitrate in kbps, one of [`SUPPORTED_BITRATES`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_wav_to_mp3_with_tag(wav_path: &str, mp3_path: &str, tag: &Id3Tag, bitrate: u32) -> Result<(), ConversionError> {
+        Self::encode(wav_path, mp3_path, Some(tag), &Mp3EncoderConfig::with_bitrate(bitrate), None)?;
+        Ok(())
+    }
+
+    /// Extracts the source MML embedded by [`Id3Tag::add_mml_source`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mp3_path` - Path to an MP3 file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Some(mml))` if the file carries embedded MML, `Ok(None)` if it
+    /// does not, or `Err(ConversionError)` if the file cannot be read.
+    pub fn read_embedded_mml(mp3_path: &str) -> Result<Option<String>, ConversionError> {
+        let mut file = File::open(mp3_path)
+            .map_err(|e| ConversionError::io("Failed to open MP3 file", e))?;
+
+        let mut header = [0u8; 10];
+        if file.read_exact(&mut header).is_err() {
+            return Ok(None);
+        }
+        let Some(tag_size) = Id3Tag::tag_size(&header) else {
+            return Ok(None);
+        };
+
+        let mut tag = header.to_vec();
+        file.take((tag_size - header.len()) as u64).read_to_end(&mut tag)
+            .map_err(|e| ConversionError::io("Failed to read ID3 tag", e))?;
+
+        Ok(Id3Tag::read_user_text(&tag, crate::id3::MML_SOURCE_DESCRIPTION))
+    }
+
+    /// Encodes a WAV file, optionally prepending `tag` and normalizing the audio first
+    /// 
+    /// Returns the audio as encoded, see [`EncodedAudio`].
+    pub(crate) fn encode(
+        wav_path: &str,
+        mp3_path: &str,
+        tag: Option<&Id3Tag>,
+        config: &Mp3EncoderConfig,
+        normalization: Option<Normalization>,
+    ) -> Result<EncodedAudio, ConversionError> {
+        config.validate()?;
+
+        let wav_file = File::open(wav_path)
+            .map_err(|e| ConversionError::io("Failed to open WAV file", e))?;
+        let mp3_file = File::create(mp3_path)
+            .map_err(|e| ConversionError::io("Failed to create MP3 file", e))?;
+
+        Self::encode_reader(BufReader::new(wav_file), BufWriter::new(mp3_file), tag, config, normalization)
+    }
+
+    /// Encodes WAV data from any reader, such as a network stream, to MP3
+    /// 
+    /// The WAV header is parsed from `reader` with the default encoder settings,
+    /// and the whole MP3 stream is written to `mp3_out`. Nothing touches the
+    /// filesystem. Reads are not buffered, so wrap unbuffered sources such as
+    /// sockets in a [`std::io::BufReader`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Source of a complete WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_out` - Sink for the MP3 data
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use std::io::BufReader;
+    /// use std::net::TcpStream;
+    /// use yks_converter_example::mp3_encoder::Mp3Encoder;
+    /// 
+    /// let upload = BufReader::new(TcpStream::connect("127.0.0.1:9000")?);
+    /// let mp3_file = std::fs::File::create("upload.mp3")?;
+    /// Mp3Encoder::encode_wav_reader(upload, mp3_file)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_wav_reader<R: Read>(reader: R, mp3_out: impl Write) -> Result<(), ConversionError> {
+        Self::encode_wav_reader_with_config(reader, mp3_out, &Mp3EncoderConfig::default())
+    }
+
+    /// Encodes WAV data from any reader using the bitrate and metadata in `config`
+    /// 
+    /// Like [`Mp3Encoder::encode_wav_reader`], but writes the ID3 tag built
+    /// from `config` in front of the audio, as
+    /// [`Mp3Encoder::convert_wav_to_mp3_with_config`] does.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Source of a complete WAV file
+    /// * `mp3_out` - Sink for the MP3 data
+    /// * `config` - Encoder settings and ID3 metadata
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// WAV input with more than two channels is rejected unless
+    /// [`Mp3EncoderConfig::downmix_multichannel`] is set:
+    /// 
+    /// ```
+    /// use std::io::Cursor;
+    /// use hound::{SampleFormat, WavSpec, WavWriter};
+    /// use yks_converter_example::mp3_encoder::{Mp3Encoder, Mp3EncoderConfig};
+    /// 
+    /// let spec = WavSpec { channels: 4, sample_rate: 44100, bits_per_sample: 16, sample_format: SampleFormat::Int };
+    /// let mut wav_data = Cursor::new(Vec::new());
+    /// let mut writer = WavWriter::new(&mut wav_data, spec)?;
+    /// for sample in [1000i16, -1000, 500, -500].repeat(441) {
+    ///     writer.write_sample(sample)?;
+    /// }
+    /// writer.finalize()?;
+    /// 
+    /// wav_data.set_position(0);
+    /// let error = Mp3Encoder::encode_wav_reader_with_config(wav_data, Vec::new(), &Mp3EncoderConfig::default()).unwrap_err();
+    /// assert!(error.to_string().contains("found 4 channels"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    /// 
+    /// Truncated WAV data is a problem with the input file, not with the
+    /// arguments, so it is reported as a WAV error whether the data chunk
+    /// ends partway through a frame or the file ends before the data chunk:
+    /// 
+    /// ```
+    /// use std::io::Cursor;
+    /// use hound::{SampleFormat, WavSpec, WavWriter};
+    /// use yks_converter_example::error::ConversionError;
+    /// use yks_converter_example::mp3_encoder::{Mp3Encoder, Mp3EncoderConfig};
+    /// 
+    /// let spec = WavSpec { channels: 2, sample_rate: 44100, bits_per_sample: 16, sample_format: SampleFormat::Int };
+    /// let stereo_wav = |sample_count: usize| -> Result<Vec<u8>, hound::Error> {
+    ///     let mut wav_data = Cursor::new(Vec::new());
+    ///     let mut writer = WavWriter::new(&mut wav_data, spec)?;
+    ///     for sample in [1000i16, -1000].into_iter().cycle().take(sample_count) {
+    ///         writer.write_sample(sample)?;
+    ///     }
+    ///     writer.finalize()?;
+    ///     Ok(wav_data.into_inner())
+    /// };
+    /// 
+    /// let odd_sample_count = stereo_wav(4411)?;
+    /// let mut cut_off = stereo_wav(4410)?;
+    /// cut_off.truncate(cut_off.len() - 2);
+    /// 
+    /// for wav_data in [odd_sample_count, cut_off] {
+    ///     let error = Mp3Encoder::encode_wav_reader_with_config(&wav_data[..], Vec::new(), &Mp3EncoderConfig::default()).unwrap_err();
+    ///     assert!(matches!(error, ConversionError::Wav { .. }), "unexpected error: {}", error);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_wav_reader_with_config<R: Read>(reader: R, mp3_out: impl Write, config: &Mp3EncoderConfig) -> Result<(), ConversionError> {
+        config.validate()?;
+        let tag = config.to_id3_tag();
+        Self::encode_reader(reader, mp3_out, Some(&tag).filter(|tag| !tag.is_empty()), config, None)?;
+        Ok(())
+    }
+
+    /// Encodes WAV data read from `wav_in` to `mp3_out`, optionally prepending
+    /// `tag` and normalizing the audio first
+    /// 
+    /// Returns the audio as encoded, see [`EncodedAudio`].
+    fn encode_reader<R: Read, W: Write>(
+        wav_in: R,
+        mut mp3_out: W,
+        tag: Option<&Id3Tag>,
+        config: &Mp3EncoderConfig,
+        normalization: Option<Normalization>,
+    ) -> Result<EncodedAudio, ConversionError> {
+        let reader = WavReader::new(wav_in)
+            .map_err(|e| ConversionError::wav("Failed to open WAV file", e))?;
+
         let spec = reader.spec();
-        if spec.sample_format != SampleFormat::Int || spec.bits_per_sample != 16 {
-            return Err("Only 16-bit integer WAV files are supported".to_string());
+        let mut samples = Self::read_samples(reader, config.dither)?;
+
+        if let Some(normalization) = normalization {
+            normalization.apply(&mut samples, spec.channels, spec.sample_rate)?;
+        }
+
+        let mut bytes_written = 0;
+        if let Some(tag) = tag {
+            let tag_bytes = tag.to_bytes();
+            mp3_out.write_all(&tag_bytes)
+                .map_err(|e| ConversionError::io("Failed to write ID3 tag", e))?;
+            bytes_written += tag_bytes.len() as u64;
         }
 
-        let mut encoder = LameEncoder::new(spec.sample_rate, spec.channels, 192)?; // Higher bitrate for better quality
-        
+        bytes_written += Self::encode_samples(&samples, spec.channels, spec.sample_rate, config, &mut mp3_out)?;
+
+        mp3_out.flush().map_err(|e| ConversionError::io("Failed to flush MP3 data", e))?;
+
+        let output = Mp3Result {
+            bytes_written,
+            frames_encoded: samples.len() / spec.channels.max(1) as usize,
+            sample_rate: spec.sample_rate,
+        };
+        Ok(EncodedAudio { samples, channels: spec.channels, sample_rate: spec.sample_rate, output })
+    }
+
+    /// Reads all samples of a WAV file as 16-bit PCM
+    /// 
+    /// Integer samples wider than 16 bits and float samples are normalized to
+    /// -1.0..1.0 and requantized to 16-bit, with TPDF dither if `dither` is set.
+    fn read_samples<R: Read>(mut reader: WavReader<R>, dither: bool) -> Result<Vec<i16>, ConversionError> {
+        let spec = reader.spec();
+        let to_i16 = if dither { dither::apply_tpdf } else { dither::quantize };
+        let samples: Result<Vec<i16>, _> = match (spec.sample_format, spec.bits_per_sample) {
+            (SampleFormat::Int, 16) => reader.samples::<i16>().collect(),
+            (SampleFormat::Int, bits @ 17..=32) => {
+                let scale = (1u64 << (bits - 1)) as f64;
+                reader.samples::<i32>()
+                    .map(|sample| sample.map(|sample| to_i16((sample as f64 / scale) as f32)))
+                    .collect()
+            }
+            (SampleFormat::Float, 32) => reader.samples::<f32>()
+                .map(|sample| sample.map(to_i16))
+                .collect(),
+            (format, bits) => {
+                return Err(ConversionError::UnsupportedWavFormat(format!(
+                    "Unsupported WAV sample format: {}-bit {:?} (supported: 16/24/32-bit integer, 32-bit float)",
+                    bits, format
+                )));
+            }
+        };
+        samples.map_err(|e| ConversionError::wav("Failed to read samples", e))
+    }
+
+    /// Converts a WAV file to MP3 using an existing encoder
+    /// 
+    /// Batch jobs can create one [`LameEncoder`] and pass it here for every file
+    /// instead of setting up LAME per file. The encoder is reset after each
+    /// file, ready for the next one. All files must have the sample rate and
+    /// channel count the encoder was created with.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `encoder` - Encoder to reuse
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float, mono or stereo)
+    /// * `mp3_path` - Path for the output MP3 file
+    /// * `dither` - Apply TPDF dither when reducing 24/32-bit or float input to
+    ///   16-bit, see [`Mp3EncoderConfig::dither`]; `false` gives bit-exact output
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the WAV format differs
+    /// from the encoder's or encoding fails.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::lame_bindings::LameEncoder;
+    /// use yks_converter_example::mp3_encoder::Mp3Encoder;
+    /// 
+    /// let mut encoder = LameEncoder::new(44100, 2, 192)?;
+    /// for name in ["intro", "theme", "outro"] {
+    ///     Mp3Encoder::convert_wav_to_mp3_with_encoder(&mut encoder, &format!("{}.wav", name), &format!("{}.mp3", name), true)?;
+    /// }
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_wav_to_mp3_with_encoder(encoder: &mut LameEncoder, wav_path: &str, mp3_path: &str, dither: bool) -> Result<(), ConversionError> {
+        let reader = WavReader::open(wav_path)
+            .map_err(|e| ConversionError::wav("Failed to open WAV file", e))?;
+
+        let spec = reader.spec();
+        encoder.check_format(spec.sample_rate, spec.channels)?;
+        let samples = Self::read_samples(reader, dither)?;
+
         let mut mp3_file = BufWriter::new(
-            File::create(mp3_path).map_err(|e| format!("Failed to create MP3 file: {}", e))?
+            File::create(mp3_path).map_err(|e| ConversionError::io("Failed to create MP3 file", e))?
         );
+        let result = Self::encode_stream(encoder, &samples, spec.channels, &mut mp3_file).map(|_| ());
+
+        // Leave the encoder ready for the next file, even if this one failed
+        encoder.reset()?;
+        result?;
+
+        mp3_file.flush().map_err(|e| ConversionError::io("Failed to flush MP3 file", e))
+    }
+
+    /// Encodes PCM samples held in memory to MP3 data
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - 16-bit PCM samples, interleaved when stereo
+    /// * `channels` - Number of channels (1 or 2)
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `bitrate` - Bitrate in kbps, one of [`SUPPORTED_BITRATES`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with the encoded MP3 stream, or `Err(ConversionError)` with error message.
+    pub fn encode_pcm(samples: &[i16], channels: u16, sample_rate: u32, bitrate: u32) -> Result<Vec<u8>, ConversionError> {
+        Self::encode_pcm_with_config(samples, channels, sample_rate, &Mp3EncoderConfig::with_bitrate(bitrate))
+    }
+
+    /// Reports whether the first MP3 frame carries a CRC checksum
+    /// 
+    /// A leading ID3v2 tag is skipped. The protection bit in the frame header
+    /// is 0 when a CRC follows the header, see [`Mp3EncoderConfig::error_protection`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mp3_data` - MP3 stream, optionally starting with an ID3v2 tag
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Some(true)` if the first frame is CRC-protected, `Some(false)` if
+    /// not, or `None` if no frame header was found.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use yks_converter_example::mp3_encoder::{Mp3Encoder, Mp3EncoderConfig};
+    /// 
+    /// // MPEG-1 Layer III frame headers with and without the protection bit cleared
+    /// assert_eq!(Mp3Encoder::is_crc_protected(&[0xff, 0xfa, 0x90, 0x00]), Some(true));
+    /// assert_eq!(Mp3Encoder::is_crc_protected(&[0xff, 0xfb, 0x90, 0x00]), Some(false));
+    /// 
+    /// let config = Mp3EncoderConfig { error_protection: true, ..Mp3EncoderConfig::default() };
+    /// let silence = vec![0i16; 44100 * 2];
+    /// let mp3_data = Mp3Encoder::encode_pcm_with_config(&silence, 2, 44100, &config)?;
+    /// assert_eq!(Mp3Encoder::is_crc_protected(&mp3_data), Some(true));
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn is_crc_protected(mp3_data: &[u8]) -> Option<bool> {
+        let start = Id3Tag::tag_size(mp3_data).unwrap_or(0).min(mp3_data.len());
+        mp3_data[start..]
+            .windows(2)
+            // 11 sync bits, then a layer field of 00 is reserved
+            .find(|header| header[0] == 0xff && header[1] & 0xe0 == 0xe0 && header[1] & 0x06 != 0)
+            .map(|header| header[1] & 0x01 == 0)
+    }
+
+    /// Encodes PCM samples held in memory using the bitrate and channel mode in `config`
+    /// 
+    /// The metadata in `config` is not written; prepend [`Mp3EncoderConfig::to_id3_tag`]
+    /// yourself if needed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - 16-bit PCM samples, interleaved when stereo
+    /// * `channels` - Number of channels (1 or 2)
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `config` - Encoder settings
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with the encoded MP3 stream, or `Err(ConversionError)` with error message.
+    pub fn encode_pcm_with_config(samples: &[i16], channels: u16, sample_rate: u32, config: &Mp3EncoderConfig) -> Result<Vec<u8>, ConversionError> {
+        config.validate()?;
+
+        let mut mp3_data = Vec::new();
+        Self::encode_samples(samples, channels, sample_rate, config, &mut mp3_data)?;
+        Ok(mp3_data)
+    }
+
+    /// Encodes interleaved stereo samples held in memory, e.g. from
+    /// [`MidiConverter::convert_midi_to_interleaved`](crate::midi_converter::MidiConverter::convert_midi_to_interleaved)
+    /// 
+    /// The samples go to LAME as they are (`lame_encode_buffer_interleaved`),
+    /// without being split into separate channels first.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pcm` - Interleaved 16-bit stereo samples (L, R, L, R, ...)
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `config` - Encoder settings
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with the encoded MP3 stream, or `Err(ConversionError)` with error message.
+    pub fn encode_interleaved(pcm: &[i16], sample_rate: u32, config: &Mp3EncoderConfig) -> Result<Vec<u8>, ConversionError> {
+        Self::encode_pcm_with_config(pcm, 2, sample_rate, config)
+    }
+
+    /// Feeds PCM samples through LAME in 1152-sample frames, writing MP3 data to `output`
+    /// 
+    /// Returns the number of MP3 bytes written.
+    fn encode_samples<W: Write>(
+        samples: &[i16],
+        channels: u16,
+        sample_rate: u32,
+        config: &Mp3EncoderConfig,
+        output: &mut W,
+    ) -> Result<u64, ConversionError> {
+        let downmixed;
+        let (samples, channels) = if channels > 2 && config.downmix_multichannel {
+            check_whole_frames(samples, channels)?;
+            downmixed = audio_utils::downmix_to_stereo(samples, channels).ok_or_else(|| {
+                ConversionError::UnsupportedWavFormat(format!(
+                    "Cannot downmix {} channels to stereo; only layouts with 3 to 8 channels are supported",
+                    channels
+                ))
+            })?;
+            (downmixed.as_slice(), 2)
+        } else {
+            (samples, channels)
+        };
+
+        if channels != 1 && channels != 2 {
+            return Err(ConversionError::UnsupportedWavFormat(format!(
+                "Only mono and stereo WAV files are supported, found {} channels (enable `downmix_multichannel` to mix them down to stereo)",
+                channels
+            )));
+        }
+
+        if config.force_mono && channels == 2 {
+            check_whole_frames(samples, channels)?;
+            let mono = audio_utils::downmix_interleaved_stereo(samples);
+            let mut encoder = LameEncoder::create(sample_rate, 1, config.bitrate, Some(StereoMode::Mono), config.quality, config.out_sample_rate)?;
+            Self::apply_frame_options(&mut encoder, config)?;
+            return Self::encode_stream(&mut encoder, &mono, 1, output);
+        }
+
+        let mut encoder = LameEncoder::create(sample_rate, channels, config.bitrate, config.stereo_mode, config.quality, config.out_sample_rate)?;
+        Self::apply_frame_options(&mut encoder, config)?;
+        Self::encode_stream(&mut encoder, samples, channels, output)
+    }
+
+    /// Applies the CRC and bit reservoir settings of `config` to a new encoder
+    fn apply_frame_options(encoder: &mut LameEncoder, config: &Mp3EncoderConfig) -> Result<(), ConversionError> {
+        if config.error_protection {
+            encoder.set_error_protection(true)?;
+        }
+        if config.disable_reservoir {
+            encoder.set_disable_reservoir(true)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes one complete MP3 stream with an already configured encoder and flushes it
+    /// 
+    /// Returns the number of MP3 bytes written.
+    fn encode_stream<W: Write>(
+        encoder: &mut LameEncoder,
+        samples: &[i16],
+        channels: u16,
+        output: &mut W,
+    ) -> Result<u64, ConversionError> {
+        check_whole_frames(samples, channels)?;
 
         const BUFFER_SIZE: usize = 1152; // MP3 frame size
-        let mut mp3_buffer = vec![0u8; 7200]; // 1.25 * BUFFER_SIZE + 7200 for safety
-        
-        if spec.channels == 1 {
-            // Mono processing
-            let mut mono_buffer = Vec::new();
-            for sample in reader.samples::<i16>() {
-                mono_buffer.push(sample.map_err(|e| format!("Failed to read sample: {}", e))?);
-                
-                if mono_buffer.len() >= BUFFER_SIZE {
-                    // Duplicate mono to stereo for LAME
-                    let stereo_left = mono_buffer[..BUFFER_SIZE].to_vec();
-                    let stereo_right = stereo_left.clone();
-                    
-                    let encoded_size = encoder.encode_buffer(&stereo_left, &stereo_right, &mut mp3_buffer)?;
-                    if encoded_size > 0 {
-                        mp3_file.write_all(&mp3_buffer[..encoded_size])
-                            .map_err(|e| format!("Failed to write MP3 data: {}", e))?;
-                    }
-                    
-                    mono_buffer.clear();
-                }
-            }
-            
-            // Process remaining samples
-            if !mono_buffer.is_empty() {
-                mono_buffer.resize(BUFFER_SIZE, 0); // Pad with zeros
-                let stereo_left = mono_buffer;
-                let stereo_right = stereo_left.clone();
-                
-                let encoded_size = encoder.encode_buffer(&stereo_left, &stereo_right, &mut mp3_buffer)?;
-                if encoded_size > 0 {
-                    mp3_file.write_all(&mp3_buffer[..encoded_size])
-                        .map_err(|e| format!("Failed to write MP3 data: {}", e))?;
-                }
-            }
-            
-        } else if spec.channels == 2 {
-            // Stereo processing
-            let samples: Result<Vec<i16>, _> = reader.samples::<i16>().collect();
-            let samples = samples.map_err(|e| format!("Failed to read samples: {}", e))?;
-            
-            for chunk in samples.chunks(BUFFER_SIZE * 2) {
-                let mut left = Vec::new();
-                let mut right = Vec::new();
-                
-                for pair in chunk.chunks_exact(2) {
-                    left.push(pair[0]);
-                    right.push(pair[1]);
-                }
-                
+        let mut mp3_buffer = vec![0u8; lame_bindings::lame_mp3_buffer_size(BUFFER_SIZE)];
+        let mut bytes_written = 0;
+
+        for chunk in samples.chunks(BUFFER_SIZE * channels as usize) {
+            let encoded_size = if channels == 2 {
+                // LAME reads interleaved stereo directly, no need to split it
+                encoder.encode_buffer_interleaved(chunk, &mut mp3_buffer)?
+            } else {
+                // LAME only reads the left buffer of a mono stream
+                let mut mono = chunk.to_vec();
+
                 // Pad if necessary
-                if left.len() < BUFFER_SIZE {
-                    left.resize(BUFFER_SIZE, 0);
-                    right.resize(BUFFER_SIZE, 0);
-                }
-                
-                let encoded_size = encoder.encode_buffer(&left, &right, &mut mp3_buffer)?;
-                if encoded_size > 0 {
-                    mp3_file.write_all(&mp3_buffer[..encoded_size])
-                        .map_err(|e| format!("Failed to write MP3 data: {}", e))?;
+                if mono.len() < BUFFER_SIZE {
+                    mono.resize(BUFFER_SIZE, 0);
                 }
+
+                encoder.encode_buffer(&mono, &mono, &mut mp3_buffer)?
+            };
+            if encoded_size > 0 {
+                output.write_all(&mp3_buffer[..encoded_size])
+                    .map_err(|e| ConversionError::io("Failed to write MP3 data", e))?;
+                bytes_written += encoded_size as u64;
             }
-        } else {
-            return Err("Only mono and stereo WAV files are supported".to_string());
         }
-        
+
         // Flush encoder
         let encoded_size = encoder.flush(&mut mp3_buffer)?;
         if encoded_size > 0 {
-            mp3_file.write_all(&mp3_buffer[..encoded_size])
-                .map_err(|e| format!("Failed to write final MP3 data: {}", e))?;
+            output.write_all(&mp3_buffer[..encoded_size])
+                .map_err(|e| ConversionError::io("Failed to write final MP3 data", e))?;
+            bytes_written += encoded_size as u64;
         }
-        
-        mp3_file.flush().map_err(|e| format!("Failed to flush MP3 file: {}", e))?;
-        
+
+        Ok(bytes_written)
+    }
+}
+
+/// Fails if interleaved `samples` passed in by the caller end partway through a frame
+/// 
+/// WAV input never gets here with a partial frame: hound rejects such data
+/// chunks when the file is opened, which is reported as a WAV error.
+fn check_whole_frames(samples: &[i16], channels: u16) -> Result<(), ConversionError> {
+    if samples.len().is_multiple_of(channels as usize) {
         Ok(())
+    } else {
+        Err(ConversionError::InvalidArgument(format!(
+            "Sample count {} is not a multiple of the channel count {}",
+            samples.len(),
+            channels
+        )))
     }
 }
\ No newline at end of file
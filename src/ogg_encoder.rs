@@ -0,0 +1,95 @@
+/*!
+ * OGG Vorbis Encoder Module
+ *
+ * Handles conversion from WAV format to OGG Vorbis using libvorbis.
+ * Only available with the `ogg` feature.
+ */
+
+use crate::error::ConversionError;
+use crate::vorbis_bindings::VorbisEncoder;
+use hound::{SampleFormat, WavReader};
+use std::fs::File;
+use std::io::{BufWriter, Read};
+
+/// Default Vorbis VBR quality, roughly 192 kbps for stereo 44.1 kHz audio
+pub const DEFAULT_QUALITY: f32 = 0.6;
+
+/// OGG Vorbis encoder using libvorbis
+/// 
+/// Mirrors [`Mp3Encoder`](crate::mp3_encoder::Mp3Encoder), but encodes with
+/// variable bitrate controlled by a quality value instead of a fixed bitrate.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use yks_converter_example::ogg_encoder::OggEncoder;
+/// 
+/// OggEncoder::convert_wav_to_ogg("song.wav", "song.ogg", 0.6)?;
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
+/// ```
+pub struct OggEncoder;
+
+impl OggEncoder {
+    /// Converts a WAV file to OGG Vorbis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_path` - Path to the input WAV file (16/24/32-bit integer or 32-bit float)
+    /// * `ogg_path` - Path for the output OGG file
+    /// * `quality` - VBR quality from -0.1 (smallest) to 1.0 (best); see [`DEFAULT_QUALITY`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_wav_to_ogg(wav_path: &str, ogg_path: &str, quality: f32) -> Result<(), ConversionError> {
+        let reader = WavReader::open(wav_path)
+            .map_err(|e| ConversionError::wav("Failed to open WAV file", e))?;
+
+        let spec = reader.spec();
+        let samples = Self::read_samples(reader)?;
+
+        let ogg_file = BufWriter::new(
+            File::create(ogg_path).map_err(|e| ConversionError::io("Failed to create OGG file", e))?
+        );
+
+        let mut encoder = VorbisEncoder::new(spec.sample_rate, spec.channels, quality, ogg_file)?;
+        encoder.encode(&samples)?;
+        encoder.finish()
+    }
+
+    /// Checks that a Vorbis quality is within -0.1..=1.0
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the accepted range.
+    pub fn validate_quality(quality: f32) -> Result<(), ConversionError> {
+        if (-0.1..=1.0).contains(&quality) {
+            Ok(())
+        } else {
+            Err(ConversionError::InvalidArgument(format!("Vorbis quality must be between -0.1 and 1.0, got {}", quality)))
+        }
+    }
+
+    /// Reads all samples of a WAV file as floats in -1.0..1.0
+    /// 
+    /// Vorbis works on floating-point audio, so no dithering is needed.
+    fn read_samples<R: Read>(mut reader: WavReader<R>) -> Result<Vec<f32>, ConversionError> {
+        let spec = reader.spec();
+        let samples: Result<Vec<f32>, _> = match (spec.sample_format, spec.bits_per_sample) {
+            (SampleFormat::Int, bits @ 16..=32) => {
+                let scale = (1u64 << (bits - 1)) as f32;
+                reader.samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 / scale))
+                    .collect()
+            }
+            (SampleFormat::Float, 32) => reader.samples::<f32>().collect(),
+            (format, bits) => {
+                return Err(ConversionError::UnsupportedWavFormat(format!(
+                    "Unsupported WAV sample format: {}-bit {:?} (supported: 16/24/32-bit integer, 32-bit float)",
+                    bits, format
+                )));
+            }
+        };
+        samples.map_err(|e| ConversionError::wav("Failed to read samples", e))
+    }
+}
\ No newline at end of file
@@ -7,11 +7,270 @@
  * - LAME for WAV→MP3 encoding
  */
 
-use crate::mml_converter::MmlConverter;
-use crate::midi_converter::MidiConverter;
-use crate::mp3_encoder::Mp3Encoder;
+use crate::audio_utils;
+use crate::error::ConversionError;
+#[cfg(feature = "flac")]
+use crate::flac_bindings;
+#[cfg(feature = "flac")]
+use crate::flac_encoder::{self, FlacEncoder};
+use crate::id3::Id3Tag;
+use crate::mml_converter::{self, MmlConverter, ValidationIssue};
+use crate::midi_converter::{self, Deadline, MidiConverter, ReleaseTail, StopConditions, SynthReport};
+use crate::midi_file::MidiFile;
+use crate::mp3_encoder::{self, EncodedAudio, Mp3Encoder, Mp3EncoderConfig, Normalization};
+#[cfg(feature = "ogg")]
+use crate::ogg_encoder::{self, OggEncoder};
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Counter distinguishing the temporary files of conversions within this process
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Intermediate files of a single conversion run
+/// 
+/// Only the synthesized WAV goes through the filesystem; MIDI data stays in
+/// memory and is written to `midi` only when intermediates are kept for
+/// inspection. Names combine the process id with a per-process counter, so
+/// concurrent conversions (in this or another process) never share files.
+struct TempFiles {
+    midi: String,
+    wav: String,
+}
+
+impl TempFiles {
+    fn new(temp_dir: &Path) -> Result<Self, ConversionError> {
+        let id = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let base = temp_dir.join(format!("yks_conversion_{}_{}", std::process::id(), id));
+        let base = base.to_str()
+            .ok_or_else(|| ConversionError::InvalidArgument("Temporary directory path is not valid UTF-8".to_string()))?;
+
+        Ok(TempFiles {
+            midi: format!("{}.mid", base),
+            wav: format!("{}.wav", base),
+        })
+    }
+}
+
+/// Per-file result of [`ConversionPipeline::validate_directory`]
+pub type ValidationReport = Vec<(PathBuf, Result<(), Vec<ValidationIssue>>)>;
+
+/// Statistics about a finished conversion, see [`ConversionPipeline::convert_mml_to_mp3_with_stats`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionStats {
+    /// Size of the MP3 file in bytes, including any ID3 tag
+    pub output_bytes: u64,
+    /// Playing time of the synthesized audio in seconds
+    pub duration_secs: f64,
+    /// Largest absolute sample value produced by the synthesizer, before any normalization
+    pub peak_amplitude: u16,
+    /// Whether any synthesized sample hit full scale
+    pub was_clipped: bool,
+    /// Integrated loudness of the encoded audio in LUFS (ITU-R BS.1770), see
+    /// [`crate::audio_utils::measure_lufs`]; streaming services target about -14
+    pub loudness_lufs: f64,
+    /// Min/max sample pairs of the encoded audio for drawing a waveform
+    /// overview, if enabled with [`ConversionPipeline::set_waveform_buckets`];
+    /// see [`crate::audio_utils::compute_peaks`]
+    pub waveform: Option<Vec<(i16, i16)>>,
+    /// Wall-clock time spent in each stage of the conversion
+    pub timings: StageTimings,
+}
+
+/// Wall-clock time of each conversion stage, see [`ConversionPipeline::convert_mml_to_mp3_timed`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageTimings {
+    /// Parsing the MML and generating MIDI data
+    pub mml_to_midi: Duration,
+    /// Synthesizing the MIDI data into the intermediate WAV file
+    pub midi_to_wav: Duration,
+    /// Reading the WAV file, normalizing and encoding it to MP3
+    pub wav_to_mp3: Duration,
+}
+
+impl StageTimings {
+    /// Returns the combined time of all stages
+    pub fn total(&self) -> Duration {
+        self.mml_to_midi + self.midi_to_wav + self.wav_to_mp3
+    }
+}
+
+/// Kind of input file, as detected by [`InputKind::detect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// Mabinogi MML text
+    Mml,
+    /// Standard MIDI file
+    Midi,
+    /// Neither MML nor MIDI, or the file could not be read
+    Unknown,
+}
+
+impl InputKind {
+    /// Detects whether `path` is an MML or MIDI file
+    /// 
+    /// The extension decides when it is `.mml`, `.mid` or `.midi`
+    /// (case-insensitive). Any other file is sniffed: MIDI files start with the
+    /// `MThd` header, and MML files with `MML@` (after an optional BOM and
+    /// whitespace).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Input file path
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the detected kind, or `InputKind::Unknown` if it cannot be determined.
+    pub fn detect(path: &str) -> InputKind {
+        let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        if extension.eq_ignore_ascii_case("mml") {
+            return InputKind::Mml;
+        }
+        if extension.eq_ignore_ascii_case("mid") || extension.eq_ignore_ascii_case("midi") {
+            return InputKind::Midi;
+        }
+
+        let mut header = Vec::new();
+        let read = fs::File::open(path).and_then(|file| file.take(64).read_to_end(&mut header));
+        if read.is_err() {
+            return InputKind::Unknown;
+        }
+        if header.starts_with(b"MThd") {
+            return InputKind::Midi;
+        }
+        let text = header.strip_prefix("\u{feff}".as_bytes()).unwrap_or(&header);
+        let text = text.trim_ascii_start();
+        if text.len() >= 4 && text[..4].eq_ignore_ascii_case(b"MML@") {
+            InputKind::Mml
+        } else {
+            InputKind::Unknown
+        }
+    }
+}
+
+/// Audio format written by [`ConversionPipeline::convert_mml`], chosen from the output extension
+/// 
+/// OGG and FLAC are only available when built with the `ogg` and `flac` features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// MP3 encoded with LAME
+    Mp3,
+    /// Uncompressed 16-bit WAV straight from the synthesizer
+    Wav,
+    /// OGG Vorbis encoded with libvorbis
+    #[cfg(feature = "ogg")]
+    Ogg,
+    /// Lossless FLAC encoded with libFLAC
+    #[cfg(feature = "flac")]
+    Flac,
+}
+
+impl OutputFormat {
+    /// Returns every format supported by this build
+    pub fn supported() -> Vec<OutputFormat> {
+        vec![
+            OutputFormat::Mp3,
+            OutputFormat::Wav,
+            #[cfg(feature = "ogg")]
+            OutputFormat::Ogg,
+            #[cfg(feature = "flac")]
+            OutputFormat::Flac,
+        ]
+    }
+
+    /// Returns the file extension of the format, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Mp3 => "mp3",
+            OutputFormat::Wav => "wav",
+            #[cfg(feature = "ogg")]
+            OutputFormat::Ogg => "ogg",
+            #[cfg(feature = "flac")]
+            OutputFormat::Flac => "flac",
+        }
+    }
+
+    /// Picks the format matching the extension of `path` (case-insensitive)
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Output file path
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(OutputFormat)` on success, or `Err(ConversionError)` listing the
+    /// supported extensions if the extension is missing or unknown.
+    pub fn from_path(path: &str) -> Result<Self, ConversionError> {
+        let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        let supported = Self::supported();
+        supported
+            .iter()
+            .copied()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+            .ok_or_else(|| {
+                let list: Vec<String> = supported.iter().map(|format| format!(".{}", format.extension())).collect();
+                ConversionError::InvalidArgument(format!(
+                    "Unsupported output format '{}' (supported: {})",
+                    path,
+                    list.join(", ")
+                ))
+            })
+    }
+}
+
+/// Reverb parameters, see [`MidiConverter::set_reverb`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverbSettings {
+    /// Room size (0.0-1.0)
+    pub roomsize: f64,
+    /// High-frequency damping (0.0-1.0)
+    pub damping: f64,
+    /// Stereo width (0.0-100.0)
+    pub width: f64,
+    /// Output level (0.0-1.0)
+    pub level: f64,
+}
+
+/// What a conversion does when the synthesized audio is silent
+/// 
+/// Silence usually means a wrong instrument number or a SoundFont without
+/// samples for it. See [`midi_converter::SILENCE_RMS_DBFS`] for the threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SilencePolicy {
+    /// Encode the silent audio without comment
+    Ignore,
+    /// Print a warning and encode anyway
+    #[default]
+    Warn,
+    /// Fail with [`ConversionError::SilentOutput`] before writing the output
+    Error,
+}
+
+/// Severity of a status message passed to a [`LogSink`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Progress through the conversion stages
+    Info,
+    /// A problem that did not stop the conversion
+    Warning,
+}
+
+/// Receives the status messages of a pipeline, see [`ConversionPipeline::set_log_sink`]
+pub type LogSink = Arc<dyn Fn(LogLevel, &str) + Send + Sync>;
+
+/// Prints info messages to stdout and warnings to stderr
+fn console_log(level: LogLevel, message: &str) {
+    match level {
+        LogLevel::Info => println!("{}", message),
+        LogLevel::Warning => eprintln!("⚠️  Warning: {}", message),
+    }
+}
+
+/// Per-file result of [`ConversionPipeline::convert_mml_directory`]
+pub type BatchReport = Vec<(PathBuf, Result<(), ConversionError>)>;
 
 /// Complete MML to MP3 conversion pipeline
 /// 
@@ -22,105 +281,1342 @@ use std::path::Path;
 /// 
 /// 1. **MML → MIDI**: Parse MML and generate MIDI data
 /// 2. **MIDI → WAV**: Synthesize audio using FluidSynth with SoundFont
-/// 3. **WAV → MP3**: Encode to MP3 using LAME at 192kbps
+/// 3. **WAV → MP3**: Encode to MP3 using LAME (192kbps by default)
 /// 
 /// # Example
 /// 
 /// ```no_run
 /// use yks_converter_example::pipeline::ConversionPipeline;
 /// 
-/// let pipeline = ConversionPipeline::new()?;
+/// let mut pipeline = ConversionPipeline::new()?;
 /// pipeline.load_soundfont("piano.sf2")?;
 /// pipeline.convert_mml_to_mp3("song.mml", "output.mp3")?;
-/// # Ok::<(), String>(())
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
 /// ```
 pub struct ConversionPipeline {
     mml_converter: MmlConverter,
     midi_converter: MidiConverter,
+    soundfont_paths: Vec<String>,
+    gain: f64,
+    polyphony: u32,
+    silence_policy: SilencePolicy,
+    reverb: Option<ReverbSettings>,
+    release_tail: ReleaseTail,
+    embed_source: bool,
+    keep_intermediates: bool,
+    encoder_config: Mp3EncoderConfig,
+    normalization: Option<Normalization>,
+    waveform_buckets: Option<usize>,
+    deadline: Option<Deadline>,
+    preview_secs: Option<f64>,
+    temp_dir: PathBuf,
+    log_sink: LogSink,
+    #[cfg(feature = "ogg")]
+    ogg_quality: f32,
+    #[cfg(feature = "flac")]
+    flac_compression_level: u8,
 }
 
-impl ConversionPipeline {
-    /// Creates a new conversion pipeline
+impl ConversionPipeline {
+    /// Creates a new conversion pipeline
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(ConversionPipeline)` on success, or `Err(ConversionError)` with error message.
+    pub fn new() -> Result<Self, ConversionError> {
+        Self::with_sample_rate(midi_converter::DEFAULT_SAMPLE_RATE)
+    }
+
+    /// Creates a new conversion pipeline synthesizing at the given sample rate
+    /// 
+    /// The synthesis rate is also the MP3 sample rate until an output rate is
+    /// set with [`ConversionPipeline::set_encoder_config`], so it must be one of
+    /// [`crate::lame_bindings::SUPPORTED_OUT_SAMPLE_RATES`]. Below 32000 Hz the
+    /// default bitrate is lowered to 160 kbps, the most such MP3s can carry.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample_rate` - Output sample rate in Hz (8000-48000, a legal MP3 rate)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(ConversionPipeline)` on success, or `Err(ConversionError)` with error message.
+    pub fn with_sample_rate(sample_rate: u32) -> Result<Self, ConversionError> {
+        let highest_bitrate = mp3_encoder::supported_bitrates_at(sample_rate).iter().copied().max().unwrap_or(mp3_encoder::DEFAULT_BITRATE);
+        let encoder_config = Mp3EncoderConfig::with_bitrate(mp3_encoder::DEFAULT_BITRATE.min(highest_bitrate));
+        encoder_config.validate_for_input_rate(sample_rate)?;
+
+        let mml_converter = MmlConverter::new();
+        let midi_converter = MidiConverter::with_sample_rate(sample_rate)?;
+        
+        Ok(ConversionPipeline {
+            mml_converter,
+            midi_converter,
+            soundfont_paths: Vec::new(),
+            gain: midi_converter::DEFAULT_GAIN,
+            polyphony: midi_converter::DEFAULT_POLYPHONY,
+            silence_policy: SilencePolicy::default(),
+            reverb: None,
+            release_tail: ReleaseTail::default(),
+            embed_source: false,
+            keep_intermediates: false,
+            encoder_config,
+            normalization: None,
+            waveform_buckets: None,
+            deadline: None,
+            preview_secs: None,
+            temp_dir: std::env::temp_dir(),
+            log_sink: Arc::new(console_log),
+            #[cfg(feature = "ogg")]
+            ogg_quality: ogg_encoder::DEFAULT_QUALITY,
+            #[cfg(feature = "flac")]
+            flac_compression_level: flac_encoder::DEFAULT_COMPRESSION_LEVEL,
+        })
+    }
+
+    /// Loads a SoundFont file for MIDI synthesis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `soundfont_path` - Path to the SoundFont (.sf2) file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn load_soundfont(&mut self, soundfont_path: &str) -> Result<(), ConversionError> {
+        self.midi_converter.load_soundfont(soundfont_path)?;
+        self.soundfont_paths.push(soundfont_path.to_string());
+        Ok(())
+    }
+
+    /// Layers another SoundFont over those already loaded
+    /// 
+    /// See [`MidiConverter::add_soundfont`] for how presets are resolved.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `soundfont_path` - Path to the SoundFont (.sf2) file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(sfont_id)` on success, or `Err(ConversionError)` with error message.
+    pub fn add_soundfont(&mut self, soundfont_path: &str) -> Result<i32, ConversionError> {
+        let sfont_id = self.midi_converter.add_soundfont(soundfont_path, true)?;
+        self.soundfont_paths.push(soundfont_path.to_string());
+        Ok(sfont_id)
+    }
+
+    /// Sets the instrument for MML conversion
+    /// 
+    /// # Arguments
+    /// 
+    /// * `program` - MIDI program number (0-127)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn set_instrument(&mut self, program: u8) -> Result<(), ConversionError> {
+        // Set instrument for MML conversion only
+        // The MIDI file generated will contain the instrument information
+        self.mml_converter.set_instrument(program);
+        Ok(())
+    }
+
+    /// Sets the instrument for MML conversion on a specific bank
+    /// 
+    /// See [`MmlConverter::set_instrument_with_bank`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `program` - MIDI program number (0-127)
+    /// * `bank` - Bank number (0-16383), or `None` for the default bank
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the program or bank is out of range.
+    pub fn set_instrument_with_bank(&mut self, program: u8, bank: Option<u16>) -> Result<(), ConversionError> {
+        if program > 127 {
+            return Err(ConversionError::InvalidArgument(format!("Instrument number must be between 0-127, got {}", program)));
+        }
+        if let Some(bank) = bank.filter(|&bank| bank > 16383) {
+            return Err(ConversionError::InvalidArgument(format!("Bank number must be between 0-16383, got {}", bank)));
+        }
+        self.mml_converter.set_instrument_with_bank(program, bank);
+        Ok(())
+    }
+
+    /// Sets a separate instrument for each comma-separated MML voice
+    /// 
+    /// See [`MmlConverter::set_instruments`] for how voices map to channels.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `programs` - MIDI program numbers (0-127), one per voice
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if a program is out of range.
+    pub fn set_instruments(&mut self, programs: &[u8]) -> Result<(), ConversionError> {
+        if let Some(&program) = programs.iter().find(|&&program| program > 127) {
+            return Err(ConversionError::InvalidArgument(format!("Instrument number must be between 0-127, got {}", program)));
+        }
+        self.mml_converter.set_instruments(programs);
+        Ok(())
+    }
+
+    /// Sets the channel volume of each comma-separated MML voice
+    /// 
+    /// See [`MmlConverter::set_part_volumes`] for how volumes map to voices.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `volumes` - Channel volumes (0-127), one per voice
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if a volume is out of range.
+    pub fn set_part_volumes(&mut self, volumes: &[u8]) -> Result<(), ConversionError> {
+        if let Some(&volume) = volumes.iter().find(|&&volume| volume > 127) {
+            return Err(ConversionError::InvalidArgument(format!("Part volume must be between 0-127, got {}", volume)));
+        }
+        self.mml_converter.set_part_volumes(volumes);
+        Ok(())
+    }
+
+    /// Enables or disables lenient MML conversion
+    /// 
+    /// When enabled, MML that fails to parse partway through is rendered up to
+    /// the failure point instead of aborting the whole conversion.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `lenient` - `true` to keep partial output on MML parse failures
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.mml_converter.set_lenient(lenient);
+    }
+
+    /// Enables or disables expansion of `[...]n` repeat brackets in MML
+    /// 
+    /// See [`MmlConverter::set_expand_repeats`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `expand` - Whether to expand repeat brackets before conversion
+    pub fn set_expand_repeats(&mut self, expand: bool) {
+        self.mml_converter.set_expand_repeats(expand);
+    }
+
+    /// Renders MML faster or slower without changing pitch
+    /// 
+    /// See [`MmlConverter::set_tempo_scale`] for how mid-song tempo changes are handled.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - Speed factor from 0.25 to 4.0, e.g. 0.75 or 1.25 for practice
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the scale is out of range.
+    pub fn set_tempo_scale(&mut self, scale: f64) -> Result<(), ConversionError> {
+        self.mml_converter.set_tempo_scale(scale)
+    }
+
+    /// Sets the MP3 output bitrate
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bitrate` - Bitrate in kbps, one of [`crate::mp3_encoder::SUPPORTED_BITRATES`]
+    ///   that is legal at the MP3 sample rate, see [`crate::mp3_encoder::supported_bitrates_at`]
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the bitrate is not supported.
+    pub fn set_bitrate(&mut self, bitrate: u32) -> Result<(), ConversionError> {
+        let sample_rate = self.encoder_config.out_sample_rate.unwrap_or(self.midi_converter.sample_rate());
+        Mp3Encoder::validate_bitrate_at(bitrate, sample_rate)?;
+        self.encoder_config.bitrate = bitrate;
+        Ok(())
+    }
+
+    /// Sets the MP3 bitrate and the ID3 metadata written to every output
+    /// 
+    /// # Arguments
+    /// 
+    /// * `config` - Bitrate, quality, channel mode, title, artist, album and year
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the configuration is
+    /// invalid, including a bitrate LAME cannot use at the MP3 sample rate.
+    pub fn set_encoder_config(&mut self, config: Mp3EncoderConfig) -> Result<(), ConversionError> {
+        config.validate_for_input_rate(self.midi_converter.sample_rate())?;
+        self.encoder_config = config;
+        Ok(())
+    }
+
+    /// Sets the Vorbis quality used by [`ConversionPipeline::convert_mml_to_ogg`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `quality` - VBR quality from -0.1 (smallest) to 1.0 (best)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the quality is out of range.
+    #[cfg(feature = "ogg")]
+    pub fn set_ogg_quality(&mut self, quality: f32) -> Result<(), ConversionError> {
+        OggEncoder::validate_quality(quality)?;
+        self.ogg_quality = quality;
+        Ok(())
+    }
+
+    /// Sets the compression level used by [`ConversionPipeline::convert_mml_to_flac`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `level` - 0 (fastest) to 8 (smallest); the output is lossless at every level
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the level is out of range.
+    #[cfg(feature = "flac")]
+    pub fn set_flac_compression_level(&mut self, level: u8) -> Result<(), ConversionError> {
+        if level > flac_bindings::MAX_COMPRESSION_LEVEL {
+            return Err(ConversionError::InvalidArgument(format!(
+                "FLAC compression level must be between 0 and {}, got {}",
+                flac_bindings::MAX_COMPRESSION_LEVEL, level
+            )));
+        }
+        self.flac_compression_level = level;
+        Ok(())
+    }
+
+    /// Sets the synthesizer gain
+    /// 
+    /// See [`MidiConverter::set_gain`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `gain` - Gain factor (0.0-10.0)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the gain is out of range.
+    pub fn set_gain(&mut self, gain: f64) -> Result<(), ConversionError> {
+        self.midi_converter.set_gain(gain)?;
+        self.gain = gain;
+        Ok(())
+    }
+
+    /// Sets how many voices the synthesizer can play at once
+    /// 
+    /// See [`MidiConverter::set_polyphony`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `voices` - Maximum simultaneous voices (1-65535)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the value is out of range.
+    pub fn set_polyphony(&mut self, voices: u32) -> Result<(), ConversionError> {
+        self.midi_converter.set_polyphony(voices)?;
+        self.polyphony = voices;
+        Ok(())
+    }
+
+    /// Chooses how silent renders are reported
+    /// 
+    /// Defaults to [`SilencePolicy::Warn`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `policy` - Whether silence is ignored, warned about, or an error
+    pub fn set_silence_policy(&mut self, policy: SilencePolicy) {
+        self.silence_policy = policy;
+    }
+
+    /// Configures and enables the synthesizer reverb
+    /// 
+    /// See [`MidiConverter::set_reverb`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reverb` - Room size, damping, width and level
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if a value is out of range.
+    pub fn set_reverb(&mut self, reverb: ReverbSettings) -> Result<(), ConversionError> {
+        self.midi_converter.set_reverb(reverb.roomsize, reverb.damping, reverb.width, reverb.level)?;
+        self.reverb = Some(reverb);
+        Ok(())
+    }
+
+    /// Keeps synthesizing after the last MIDI event so reverb can decay
+    /// 
+    /// See [`MidiConverter::set_release_tail`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tail` - Tail length in milliseconds and whether to fade it out
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the tail is too long.
+    pub fn set_release_tail(&mut self, tail: ReleaseTail) -> Result<(), ConversionError> {
+        self.midi_converter.set_release_tail(tail)?;
+        self.release_tail = tail;
+        Ok(())
+    }
+
+    /// Enables or disables peak normalization before MP3 encoding
+    /// 
+    /// See [`Mp3Encoder::convert_wav_to_mp3_normalized`]. Peak and loudness
+    /// normalization are mutually exclusive, so this replaces any target set by
+    /// [`ConversionPipeline::set_loudness_normalization`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `target_peak_dbfs` - Desired peak level in dBFS (at most 0.0), or `None` to
+    ///   encode the synthesized level unchanged
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the target is above 0 dBFS.
+    pub fn set_peak_normalization(&mut self, target_peak_dbfs: Option<f64>) -> Result<(), ConversionError> {
+        self.set_normalization(target_peak_dbfs.map(Normalization::Peak))
+    }
+
+    /// Enables or disables loudness normalization before MP3 encoding
+    /// 
+    /// See [`Mp3Encoder::convert_wav_to_mp3_loudnorm`]. Peak and loudness
+    /// normalization are mutually exclusive, so this replaces any target set by
+    /// [`ConversionPipeline::set_peak_normalization`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `target_lufs` - Desired integrated loudness in LUFS (-60.0 to 0.0), e.g.
+    ///   -14.0 for streaming services, or `None` to encode the synthesized level unchanged
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the target is out of range.
+    pub fn set_loudness_normalization(&mut self, target_lufs: Option<f64>) -> Result<(), ConversionError> {
+        self.set_normalization(target_lufs.map(Normalization::Loudness))
+    }
+
+    /// Sets the normalization applied before MP3 encoding, after validating it
+    fn set_normalization(&mut self, normalization: Option<Normalization>) -> Result<(), ConversionError> {
+        if let Some(normalization) = normalization {
+            normalization.validate()?;
+        }
+        self.normalization = normalization;
+        Ok(())
+    }
+
+    /// Enables or disables waveform overview data in [`ConversionStats::waveform`]
+    /// 
+    /// The peaks are taken from the audio as it is encoded to MP3, after any
+    /// normalization, so a frontend can draw an overview without decoding the
+    /// MP3 or receiving the full PCM.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `buckets` - Number of min/max pairs wanted (fewer are returned for very
+    ///   short audio), or `None` to skip computing them
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// pipeline.set_waveform_buckets(Some(800));
+    /// let stats = pipeline.convert_mml_to_mp3_with_stats("song.mml", "song.mp3")?;
+    /// for (min, max) in stats.waveform.unwrap_or_default() {
+    ///     // draw a vertical line from min to max
+    /// #   let _ = (min, max);
+    /// }
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn set_waveform_buckets(&mut self, buckets: Option<usize>) {
+        self.waveform_buckets = buckets;
+    }
+
+    /// Sets the directory used for intermediate `.wav` files
+    /// 
+    /// Defaults to [`std::env::temp_dir`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dir` - Existing, writable directory
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` if the directory does not
+    /// exist or is not writable.
+    pub fn set_temp_dir(&mut self, dir: PathBuf) -> Result<(), ConversionError> {
+        if !dir.is_dir() {
+            return Err(ConversionError::not_found(format!("Temporary directory does not exist: {}", dir.display())));
+        }
+
+        let probe = dir.join(format!(".yks_write_test_{}", std::process::id()));
+        fs::write(&probe, b"")
+            .map_err(|e| ConversionError::io(format!("Temporary directory is not writable: {}", dir.display()), e))?;
+        let _ = fs::remove_file(&probe);
+
+        self.temp_dir = dir;
+        Ok(())
+    }
+
+    /// Enables or disables embedding the source MML into the output MP3
+    /// 
+    /// When enabled, the MML text is stored in an ID3v2 TXXX frame described as
+    /// `MML` and can be recovered with [`Mp3Encoder::read_embedded_mml`]. MML longer
+    /// than [`crate::id3::MAX_EMBEDDED_MML_BYTES`] is truncated.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `embed` - `true` to embed the source MML
+    pub fn set_embed_source(&mut self, embed: bool) {
+        self.embed_source = embed;
+    }
+
+    /// Keeps the intermediate MIDI and WAV files instead of deleting them
+    /// 
+    /// Useful when a conversion sounds wrong: the MIDI shows what the MML
+    /// turned into, the WAV what the synthesizer made of it. The files are
+    /// written to the temporary directory (see [`ConversionPipeline::set_temp_dir`])
+    /// under names unique to each conversion, and their paths are printed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `keep` - `true` to keep intermediate files
+    pub fn set_keep_intermediates(&mut self, keep: bool) {
+        self.keep_intermediates = keep;
+    }
+
+    /// Sets where status messages go
+    /// 
+    /// By default progress lines are printed to stdout and warnings to
+    /// stderr. Pass a sink to redirect them into an application's own
+    /// logging, or `|_, _| {}` to silence the pipeline. Worker pipelines of
+    /// [`ConversionPipeline::convert_mml_directory_parallel`] share the sink,
+    /// so it may be called from several threads at once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sink` - Called with the level and text of each message
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::{ConversionPipeline, LogLevel};
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.set_log_sink(|level, message| {
+    ///     if level == LogLevel::Warning {
+    ///         eprintln!("converter: {}", message);
+    ///     }
+    /// });
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn set_log_sink(&mut self, sink: impl Fn(LogLevel, &str) + Send + Sync + 'static) {
+        self.log_sink = Arc::new(sink);
+    }
+
+    /// Converts MML file to the format given by the output file's extension
+    /// 
+    /// `.mp3`, `.wav`, and with the matching features `.ogg` and `.flac` are
+    /// dispatched to the corresponding `convert_mml_to_*` method.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `output_path` - Path for the output file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message; an
+    /// unknown extension is reported with the list of supported ones.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// pipeline.convert_mml("song.mml", "song.wav")?;
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_mml(&mut self, mml_file_path: &str, output_path: &str) -> Result<(), ConversionError> {
+        match OutputFormat::from_path(output_path)? {
+            OutputFormat::Mp3 => self.convert_mml_to_mp3(mml_file_path, output_path),
+            OutputFormat::Wav => self.convert_mml_to_wav(mml_file_path, output_path),
+            #[cfg(feature = "ogg")]
+            OutputFormat::Ogg => self.convert_mml_to_ogg(mml_file_path, output_path),
+            #[cfg(feature = "flac")]
+            OutputFormat::Flac => self.convert_mml_to_flac(mml_file_path, output_path),
+        }
+    }
+
+    /// Converts an MML or MIDI file, choosing the stages from both file types
+    /// 
+    /// The input kind comes from [`InputKind::detect`] and the output format
+    /// from the output extension, as in [`ConversionPipeline::convert_mml`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input_path` - Path to input MML or MIDI file
+    /// * `output_path` - Path for the output file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// pipeline.convert_any("song.mml", "song.mp3")?;
+    /// pipeline.convert_any("song.mid", "song.wav")?;
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_any(&mut self, input_path: &str, output_path: &str) -> Result<(), ConversionError> {
+        match InputKind::detect(input_path) {
+            InputKind::Mml => self.convert_mml(input_path, output_path),
+            InputKind::Midi => self.convert_midi(input_path, output_path),
+            InputKind::Unknown => Err(ConversionError::InvalidArgument(format!(
+                "Unsupported input file '{}' (supported: .mml, .mid, .midi)",
+                input_path
+            ))),
+        }
+    }
+
+    /// Converts a MIDI file to the format chosen by the output extension
+    /// 
+    /// Runs the MIDI → WAV → output stages with the pipeline's SoundFonts, gain
+    /// and encoder settings. The MIDI file's own program changes pick the
+    /// instruments.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_file_path` - Path to input MIDI file
+    /// * `output_path` - Path for the output file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_midi(&mut self, midi_file_path: &str, output_path: &str) -> Result<(), ConversionError> {
+        let format = OutputFormat::from_path(output_path)?;
+        let midi_data = fs::read(midi_file_path)
+            .map_err(|e| ConversionError::io(format!("Failed to read MIDI file '{}'", midi_file_path), e))?;
+
+        let temp_files = TempFiles::new(&self.temp_dir)?;
+        let result = self.render_midi_to_format(format, &midi_data, &temp_files, output_path);
+        self.finish_temp_files(&temp_files);
+        result
+    }
+
+    /// Checks that [`ConversionPipeline::convert_mml`] could run, without producing output
+    /// 
+    /// Reads and validates the MML file, converts it to MIDI in memory,
+    /// confirms a SoundFont is loaded and the output extension is supported,
+    /// and tests that the output path is writable. No audio is synthesized, so
+    /// this returns almost immediately and is suited to checking a batch
+    /// before starting it.
+    /// 
+    /// Writability is tested by creating and deleting an empty file at
+    /// `output_path`. An existing file is opened for writing instead and left
+    /// untouched.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `output_path` - Path the output file would be written to
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` if the conversion would be able to start, or the first
+    /// `Err(ConversionError)` found.
+    pub fn dry_run(&mut self, mml_file_path: &str, output_path: &str) -> Result<(), ConversionError> {
+        OutputFormat::from_path(output_path)?;
+
+        self.midi_converter.require_soundfont()?;
+
+        let mml_text = mml_converter::read_mml_file(mml_file_path)
+            .map_err(|e| ConversionError::io(format!("Failed to read MML file '{}'", mml_file_path), e))?;
+        self.mml_converter.validate_mml(&mml_text)?;
+        self.convert_mml(&mml_text)?;
+
+        if Path::new(output_path).exists() {
+            fs::OpenOptions::new()
+                .write(true)
+                .open(output_path)
+                .map_err(|e| ConversionError::io(format!("Output file '{}' is not writable", output_path), e))?;
+        } else {
+            fs::File::create(output_path)
+                .map_err(|e| ConversionError::io(format!("Cannot create output file '{}'", output_path), e))?;
+            fs::remove_file(output_path)
+                .map_err(|e| ConversionError::io(format!("Failed to remove test file '{}'", output_path), e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Converts MML file directly to MP3
+    /// 
+    /// This is the main pipeline function that performs the complete conversion:
+    /// MML → MIDI → WAV → MP3
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_mp3(&mut self, mml_file_path: &str, mp3_output_path: &str) -> Result<(), ConversionError> {
+        self.convert_mml_to_mp3_with_stats(mml_file_path, mp3_output_path)?;
+        Ok(())
+    }
+
+    /// Converts MML file to MP3 without blocking the async runtime
+    /// 
+    /// The conversion runs on tokio's blocking thread pool via
+    /// `tokio::task::spawn_blocking`, so it must be awaited inside a tokio
+    /// runtime. The pipeline moves to that thread for the duration of the
+    /// conversion and is handed back alongside the result for reuse.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the pipeline together with `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// # async fn example() -> Result<(), yks_converter_example::error::ConversionError> {
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// let (pipeline, result) = pipeline.convert_mml_to_mp3_async("song.mml".to_string(), "output.mp3".to_string()).await;
+    /// result?;
+    /// # drop(pipeline);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn convert_mml_to_mp3_async(mut self, mml_file_path: String, mp3_output_path: String) -> (Self, Result<(), ConversionError>) {
+        tokio::task::spawn_blocking(move || {
+            let result = self.convert_mml_to_mp3(&mml_file_path, &mp3_output_path);
+            (self, result)
+        })
+        .await
+        // Blocking tasks are only cancelled when the runtime shuts down, in
+        // which case this future is never polled again either
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+    }
+
+    /// Converts MML file to MP3, normalized to a target integrated loudness
+    /// 
+    /// Uses [`Mp3Encoder::convert_wav_to_mp3_loudnorm`]'s two-pass approach:
+    /// the synthesized audio is measured, then scaled through a safety limiter.
+    /// For this conversion the loudness target takes the place of any peak or
+    /// loudness normalization set on the pipeline, which is restored afterwards.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `target_lufs` - Desired integrated loudness in LUFS (-60.0 to 0.0), e.g. -14.0
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_mp3_loudnorm(&mut self, mml_file_path: &str, mp3_output_path: &str, target_lufs: f64) -> Result<(), ConversionError> {
+        let normalization = Normalization::Loudness(target_lufs);
+        normalization.validate()?;
+
+        let previous = self.normalization.replace(normalization);
+        let result = self.convert_mml_to_mp3(mml_file_path, mp3_output_path);
+        self.normalization = previous;
+        result
+    }
+
+    /// Converts MML file to MP3, giving up once `timeout` has elapsed
+    /// 
+    /// Synthesis is the only stage whose running time is not bounded by the
+    /// input size (a huge tempo or thousands of notes can take minutes), so
+    /// the elapsed time is checked before every synthesized buffer. The
+    /// timeout is counted from the start of the call, so time spent
+    /// converting the MML counts towards it. Intermediate files are removed
+    /// and no MP3 is written when the timeout hits.
+    /// 
+    /// The timeout is checked alongside the cancellation flag of
+    /// [`MidiConverter::convert_midi_to_wav_cancellable`], so both stop a render
+    /// within one buffer, and a render given both stops on whichever comes first.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `timeout` - Longest time the conversion may take
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, `Err(ConversionError::Timeout)` if the
+    /// timeout elapsed, or another `Err(ConversionError)` on failure.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use yks_converter_example::error::ConversionError;
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// match pipeline.convert_mml_to_mp3_with_timeout("upload.mml", "upload.mp3", Duration::from_secs(30)) {
+    ///     Err(ConversionError::Timeout(_)) => eprintln!("MML too long to render"),
+    ///     result => result?,
+    /// }
+    /// # Ok::<(), ConversionError>(())
+    /// ```
+    pub fn convert_mml_to_mp3_with_timeout(&mut self, mml_file_path: &str, mp3_output_path: &str, timeout: Duration) -> Result<(), ConversionError> {
+        let previous = self.deadline.replace(Deadline::after(timeout));
+        let result = self.convert_mml_to_mp3(mml_file_path, mp3_output_path);
+        self.deadline = previous;
+        result
+    }
+
+    /// Converts only the beginning of an MML file to MP3, for checking the
+    /// instrument and mix quickly
+    /// 
+    /// Synthesis stops once `seconds` of audio have been rendered, so a
+    /// preview of a long song takes a fraction of the full conversion time.
+    /// Songs shorter than the preview are converted whole. The cut is hard:
+    /// notes still sounding at the end are not faded out.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `seconds` - Length of the preview in seconds (greater than 0)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_preview(&mut self, mml_file_path: &str, mp3_output_path: &str, seconds: f64) -> Result<(), ConversionError> {
+        validate_preview_secs(seconds)?;
+
+        let previous = self.preview_secs.replace(seconds);
+        let result = self.convert_mml_to_mp3(mml_file_path, mp3_output_path);
+        self.preview_secs = previous;
+        result
+    }
+
+    /// Converts a range of bars of an MML file to MP3, for studying a passage
+    /// 
+    /// Bar lines come from the time signatures of the generated MIDI (4/4
+    /// if it has none), see [`MidiFile::bar_range`]; a song opening with a
+    /// pickup numbers it bar 0. Tempo, instrument and controller settings
+    /// from before the range are carried over, so the passage plays at the
+    /// tempo in effect where it starts and follows tempo changes within it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `start_bar` - First bar to render, counting from 1
+    /// * `end_bar` - Last bar to render, inclusive
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message; a
+    /// range reaching past the end of the song is an `InvalidArgument` error.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// pipeline.convert_mml_bars("song.mml", "bars_9_to_16.mp3", 9, 16)?;
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_mml_bars(&mut self, mml_file_path: &str, mp3_output_path: &str, start_bar: u32, end_bar: u32) -> Result<(), ConversionError> {
+        self.convert_mml_file(mml_file_path, |pipeline, midi_data, temp_files| {
+            let midi = MidiFile::parse(midi_data)?;
+            let (start_tick, end_tick) = midi.bar_range(start_bar, end_bar)?;
+            pipeline.info(&format!("✂️  Selected bars {}-{}", start_bar, end_bar));
+
+            let excerpt = midi.extract_ticks(start_tick, end_tick).to_bytes();
+            let mml_source = pipeline.embedded_source(mml_file_path)?;
+            pipeline.render_midi_to_mp3(&excerpt, temp_files, mp3_output_path, mml_source.as_deref())
+        })?;
+        Ok(())
+    }
+
+    /// Converts MML file to MP3 and reports statistics about the result
+    /// 
+    /// The duration is the synthesized sample count over the sample rate, so it
+    /// includes reverb tails; the byte count is the length of the final MP3 file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(ConversionStats)` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_mp3_with_stats(&mut self, mml_file_path: &str, mp3_output_path: &str) -> Result<ConversionStats, ConversionError> {
+        let started = Instant::now();
+        self.convert_mml_file(mml_file_path, |pipeline, midi_data, temp_files| {
+            let mml_to_midi = started.elapsed();
+            let mml_source = pipeline.embedded_source(mml_file_path)?;
+            let mut stats = pipeline.render_midi_to_mp3(midi_data, temp_files, mp3_output_path, mml_source.as_deref())?;
+            stats.timings.mml_to_midi = mml_to_midi;
+            Ok(stats)
+        })
+    }
+
+    /// Converts MML file to MP3 and reports how long each stage took
+    /// 
+    /// Shows whether synthesis or encoding dominates a workload, e.g. when
+    /// tuning [`MidiConverter::set_buffer_size`] or the encoder quality. The
+    /// times are wall-clock times of the same conversion as
+    /// [`ConversionPipeline::convert_mml_to_mp3`], including file I/O.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(StageTimings)` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// let timings = pipeline.convert_mml_to_mp3_timed("song.mml", "output.mp3")?;
+    /// println!("Synthesis: {:?}, encoding: {:?}", timings.midi_to_wav, timings.wav_to_mp3);
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_mml_to_mp3_timed(&mut self, mml_file_path: &str, mp3_output_path: &str) -> Result<StageTimings, ConversionError> {
+        Ok(self.convert_mml_to_mp3_with_stats(mml_file_path, mp3_output_path)?.timings)
+    }
+
+    /// Converts MML file to MP3 and keeps the generated MIDI as a second deliverable
+    /// 
+    /// The MIDI is the exact data synthesized into the MP3, including the
+    /// instrument, channel layout and tempo settings of the pipeline. It is a
+    /// standard MIDI file (SMF) that DAWs and notation software can import; it
+    /// is parsed before being written, so malformed data is reported instead of
+    /// saved.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `midi_output_path` - Path for output MIDI file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use yks_converter_example::pipeline::ConversionPipeline;
+    /// 
+    /// let mut pipeline = ConversionPipeline::new()?;
+    /// pipeline.load_soundfont("piano.sf2")?;
+    /// pipeline.convert_mml_to_mp3_and_midi("song.mml", "song.mp3", "song.mid")?;
+    /// # Ok::<(), yks_converter_example::error::ConversionError>(())
+    /// ```
+    pub fn convert_mml_to_mp3_and_midi(&mut self, mml_file_path: &str, mp3_output_path: &str, midi_output_path: &str) -> Result<(), ConversionError> {
+        self.convert_mml_file(mml_file_path, |pipeline, midi_data, temp_files| {
+            MidiFile::parse(midi_data)?;
+            fs::write(midi_output_path, midi_data)
+                .map_err(|e| ConversionError::io("Failed to write MIDI file", e))?;
+            pipeline.info(&format!("✅ MIDI file saved: {}", midi_output_path));
+
+            let mml_source = pipeline.embedded_source(mml_file_path)?;
+            pipeline.render_midi_to_mp3(midi_data, temp_files, mp3_output_path, mml_source.as_deref())
+        })?;
+        Ok(())
+    }
+
+    /// Reads the MML source for embedding in the ID3 tag, or returns `None` if
+    /// embedding is disabled
+    fn embedded_source(&self, mml_file_path: &str) -> Result<Option<String>, ConversionError> {
+        if !self.embed_source {
+            return Ok(None);
+        }
+        mml_converter::read_mml_file(mml_file_path)
+            .map(Some)
+            .map_err(|e| ConversionError::io("Failed to read MML file", e))
+    }
+
+    /// Converts MML file to MP3 with the song repeated back-to-back
+    /// 
+    /// The MIDI is synthesized once and the samples are joined `repeats`
+    /// times before encoding, so the copies are identical and rendering cost
+    /// does not grow with the repeat count. The configured [`ReleaseTail`]
+    /// (see [`ConversionPipeline::set_release_tail`]) is rendered once after
+    /// the last MIDI event, faded if requested, and repeated with the rest, so
+    /// each repetition ends in its own tail before the next one starts. The
+    /// tail never bleeds into the next repetition, which starts exactly like
+    /// the first; set `tail_ms` to 0 for a cut right after the last event. A
+    /// crossfade instead overlaps the end of each repetition with the start of
+    /// the next, which hides the seam in ambience loops at the cost of a
+    /// slightly shorter file.
+    /// 
+    /// Peak normalization, ID3 metadata and the encoder settings apply to the
+    /// looped result as in [`ConversionPipeline::convert_mml_to_mp3`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `repeats` - How many times the song plays (at least 1)
+    /// * `crossfade_ms` - Overlap between repetitions in milliseconds, or 0 for a
+    ///   hard cut; limited to half the song's length
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_mp3_looped(
+        &mut self,
+        mml_file_path: &str,
+        mp3_output_path: &str,
+        repeats: u32,
+        crossfade_ms: u32,
+    ) -> Result<(), ConversionError> {
+        if repeats == 0 {
+            return Err(ConversionError::InvalidArgument("Repeat count must be at least 1".to_string()));
+        }
+
+        self.info("🎼 Converting MML to MIDI...");
+        let mml_text = mml_converter::read_mml_file(mml_file_path)
+            .map_err(|e| ConversionError::io(format!("Failed to read MML file '{}'", mml_file_path), e))?;
+        let midi_data = self.convert_mml(&mml_text)?;
+        self.info("✅ MIDI data generated");
+
+        self.info("🎹 Synthesizing MIDI...");
+        let song = self.synthesize(|converter| converter.render_midi_bytes(&midi_data))?;
+        self.check_silence(&SynthReport::from_samples(&song))?;
+        let crossfade_frames = (crossfade_ms as u64 * self.midi_converter.sample_rate() as u64 / 1000) as usize;
+        let mut samples = repeat_with_crossfade(&song, repeats as usize, crossfade_frames);
+        self.info(&format!("✅ Rendered {} repetitions", repeats));
+
+        if let Some(normalization) = self.normalization {
+            normalization.apply(&mut samples, 2, self.midi_converter.sample_rate())?;
+        }
+
+        self.info("🎵 Encoding to MP3...");
+        let mut mp3_data = self.id3_tag(Some(&mml_text)).map(|tag| tag.to_bytes()).unwrap_or_default();
+        mp3_data.extend(Mp3Encoder::encode_pcm_with_config(&samples, 2, self.midi_converter.sample_rate(), &self.encoder_config)?);
+        fs::write(mp3_output_path, mp3_data)
+            .map_err(|e| ConversionError::io("Failed to write MP3 file", e))?;
+        self.info("✅ MP3 encoding completed");
+
+        Ok(())
+    }
+
+    /// Converts MML file to WAV
+    /// 
+    /// Runs only the MML → MIDI → WAV stages with the pipeline's SoundFonts,
+    /// instruments and gain, synthesizing straight into `wav_output_path`. No
+    /// intermediate WAV or MP3 file is created.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to input MML file
+    /// * `wav_output_path` - Path for output WAV file (16-bit stereo)
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(ConversionPipeline)` on success, or `Err(String)` with error message.
-    pub fn new() -> Result<Self, String> {
-        let mml_converter = MmlConverter::new();
-        let midi_converter = MidiConverter::new()?;
-        
-        Ok(ConversionPipeline {
-            mml_converter,
-            midi_converter,
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_to_wav(&mut self, mml_file_path: &str, wav_output_path: &str) -> Result<(), ConversionError> {
+        self.convert_mml_file(mml_file_path, |pipeline, midi_data, temp_files| {
+            pipeline.render_midi_to_format(OutputFormat::Wav, midi_data, temp_files, wav_output_path)
         })
     }
 
-    /// Loads a SoundFont file for MIDI synthesis
+    /// Converts MML file to OGG Vorbis
+    /// 
+    /// Runs MML → MIDI → WAV like [`ConversionPipeline::convert_mml_to_mp3`], then
+    /// encodes with libvorbis at the quality set by [`ConversionPipeline::set_ogg_quality`].
+    /// The MP3 settings (bitrate, ID3 metadata, peak normalization) do not apply.
     /// 
     /// # Arguments
     /// 
-    /// * `soundfont_path` - Path to the SoundFont (.sf2) file
+    /// * `mml_file_path` - Path to input MML file
+    /// * `ogg_output_path` - Path for output OGG file
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn load_soundfont(&mut self, soundfont_path: &str) -> Result<(), String> {
-        self.midi_converter.load_soundfont(soundfont_path)
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    #[cfg(feature = "ogg")]
+    pub fn convert_mml_to_ogg(&mut self, mml_file_path: &str, ogg_output_path: &str) -> Result<(), ConversionError> {
+        self.convert_mml_file(mml_file_path, |pipeline, midi_data, temp_files| {
+            pipeline.render_midi_to_format(OutputFormat::Ogg, midi_data, temp_files, ogg_output_path)
+        })
     }
 
-    /// Sets the instrument for MML conversion
+    /// Converts MML file to lossless FLAC
+    /// 
+    /// Runs MML → MIDI → WAV like [`ConversionPipeline::convert_mml_to_mp3`], then
+    /// encodes the WAV samples unchanged at the level set by
+    /// [`ConversionPipeline::set_flac_compression_level`]. The MP3 settings do not apply.
     /// 
     /// # Arguments
     /// 
-    /// * `program` - MIDI program number (0-127)
+    /// * `mml_file_path` - Path to input MML file
+    /// * `flac_output_path` - Path for output FLAC file
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn set_instrument(&mut self, program: u8) -> Result<(), String> {
-        // Set instrument for MML conversion only
-        // The MIDI file generated will contain the instrument information
-        self.mml_converter.set_instrument(program);
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    #[cfg(feature = "flac")]
+    pub fn convert_mml_to_flac(&mut self, mml_file_path: &str, flac_output_path: &str) -> Result<(), ConversionError> {
+        self.convert_mml_file(mml_file_path, |pipeline, midi_data, temp_files| {
+            pipeline.render_midi_to_format(OutputFormat::Flac, midi_data, temp_files, flac_output_path)
+        })
+    }
+
+    /// Runs the stages after MIDI generation for `format`, writing `output_path`
+    /// 
+    /// WAV output is synthesized in place; the other formats are encoded from
+    /// the temporary WAV file.
+    fn render_midi_to_format(&mut self, format: OutputFormat, midi_data: &[u8], temp_files: &TempFiles, output_path: &str) -> Result<(), ConversionError> {
+        if format == OutputFormat::Mp3 {
+            return self.render_midi_to_mp3(midi_data, temp_files, output_path, None).map(|_| ());
+        }
+
+        let wav_path = if format == OutputFormat::Wav { output_path } else { temp_files.wav.as_str() };
+        self.info("🎹 Synthesizing MIDI to WAV...");
+        let report = self.synthesize(|converter| converter.convert_midi_bytes_to_wav_reporting(midi_data, wav_path))?;
+        if let Err(e) = self.check_silence(&report) {
+            if format == OutputFormat::Wav {
+                let _ = fs::remove_file(wav_path);
+            }
+            return Err(e);
+        }
+        self.info("✅ WAV file generated");
+
+        match format {
+            OutputFormat::Mp3 | OutputFormat::Wav => {},
+            #[cfg(feature = "ogg")]
+            OutputFormat::Ogg => {
+                self.info("🎵 Encoding WAV to OGG...");
+                OggEncoder::convert_wav_to_ogg(wav_path, output_path, self.ogg_quality)?;
+                self.info("✅ OGG encoding completed");
+            },
+            #[cfg(feature = "flac")]
+            OutputFormat::Flac => {
+                self.info("🎵 Encoding WAV to FLAC...");
+                FlacEncoder::convert_wav_to_flac(wav_path, output_path, self.flac_compression_level)?;
+                self.info("✅ FLAC encoding completed");
+            },
+        }
         Ok(())
     }
 
-    /// Converts MML file directly to MP3
+    /// Converts an MML file to in-memory MIDI data and hands it to `render`
+    /// for the remaining stages, removing the temporary files afterwards unless
+    /// intermediates are kept
+    fn convert_mml_file<T>(
+        &mut self,
+        mml_file_path: &str,
+        render: impl FnOnce(&mut Self, &[u8], &TempFiles) -> Result<T, ConversionError>,
+    ) -> Result<T, ConversionError> {
+        let temp_files = TempFiles::new(&self.temp_dir)?;
+
+        let result = (|| {
+            // Step 1: MML → MIDI
+            self.info("🎼 Converting MML to MIDI...");
+            let midi_data = self.mml_converter.convert_mml_file_to_midi_bytes(mml_file_path)?;
+            self.info("✅ MIDI data generated");
+            self.keep_midi(&temp_files, &midi_data)?;
+
+            render(self, &midi_data, &temp_files)
+        })();
+
+        // Clean up temporary files, even if a stage failed midway
+        self.finish_temp_files(&temp_files);
+
+        result
+    }
+
+    /// Converts every `.mml` file in a directory to a same-named `.mp3`
     /// 
-    /// This is the main pipeline function that performs the complete conversion:
-    /// MML → MIDI → WAV → MP3
+    /// Files are converted one after another with the current SoundFont,
+    /// instrument and encoder settings. A failing file is recorded in the
+    /// report and does not stop the rest of the batch.
     /// 
     /// # Arguments
     /// 
-    /// * `mml_file_path` - Path to input MML file
-    /// * `mp3_output_path` - Path for output MP3 file
+    /// * `input_dir` - Directory containing MML files (not searched recursively)
+    /// * `output_dir` - Directory for the MP3 files; created if missing
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn convert_mml_to_mp3(&mut self, mml_file_path: &str, mp3_output_path: &str) -> Result<(), String> {
-        // Generate temporary file names
-        let temp_midi_path = "temp_conversion.mid";
-        let temp_wav_path = "temp_conversion.wav";
+    /// Returns `Ok` with one entry per MML file, sorted by path, or `Err(ConversionError)`
+    /// if the input directory cannot be read or the output directory cannot be created.
+    pub fn convert_mml_directory(&mut self, input_dir: &str, output_dir: &str) -> Result<BatchReport, ConversionError> {
+        let files = mml_files_in(input_dir)?;
+        fs::create_dir_all(output_dir)
+            .map_err(|e| ConversionError::io(format!("Failed to create output directory '{}'", output_dir), e))?;
 
-        // Step 1: MML → MIDI
-        println!("🎼 Converting MML to MIDI...");
-        self.mml_converter.convert_mml_file_to_midi(mml_file_path, temp_midi_path)?;
-        println!("✅ MIDI file generated");
+        let report = files
+            .into_iter()
+            .map(|path| {
+                let result = self.convert_batch_file(&path, output_dir);
+                (path, result)
+            })
+            .collect();
 
-        // Step 2: MIDI → WAV
-        println!("🎹 Synthesizing MIDI to WAV...");
-        self.midi_converter.convert_midi_to_wav(temp_midi_path, temp_wav_path)?;
-        println!("✅ WAV file generated");
+        Ok(report)
+    }
 
-        // Step 3: WAV → MP3
-        println!("🎵 Encoding WAV to MP3...");
-        Mp3Encoder::convert_wav_to_mp3(temp_wav_path, mp3_output_path)?;
-        println!("✅ MP3 encoding completed");
+    /// Converts every `.mml` file in a directory using several worker threads
+    /// 
+    /// FluidSynth synthesizers cannot be shared, so each worker gets its own
+    /// pipeline with its own synthesizer, loading its own copy of every
+    /// SoundFont loaded into this pipeline. Instrument, gain and encoder
+    /// settings are copied as well. Workers pull files from a shared queue,
+    /// and intermediate file names are unique per conversion, so workers never
+    /// collide. The total wall-clock time is printed when the batch finishes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input_dir` - Directory containing MML files (not searched recursively)
+    /// * `output_dir` - Directory for the MP3 files; created if missing
+    /// * `num_threads` - Number of worker threads (at least 1)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok` with one entry per MML file, sorted by path, or `Err(ConversionError)`
+    /// if the directories cannot be accessed or a worker cannot be set up.
+    pub fn convert_mml_directory_parallel(&self, input_dir: &str, output_dir: &str, num_threads: usize) -> Result<BatchReport, ConversionError> {
+        if num_threads == 0 {
+            return Err(ConversionError::InvalidArgument("Thread count must be at least 1".to_string()));
+        }
 
-        // Clean up temporary files
-        self.cleanup_temp_files(&[temp_midi_path, temp_wav_path]);
+        let files = mml_files_in(input_dir)?;
+        fs::create_dir_all(output_dir)
+            .map_err(|e| ConversionError::io(format!("Failed to create output directory '{}'", output_dir), e))?;
 
-        Ok(())
+        let started = Instant::now();
+        let workers = (0..num_threads.min(files.len()))
+            .map(|_| self.worker())
+            .collect::<Result<Vec<_>, _>>()?;
+        let thread_count = workers.len();
+
+        let next_file = AtomicUsize::new(0);
+        let mut results: Vec<(usize, Result<(), ConversionError>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = workers
+                .into_iter()
+                .map(|mut worker| {
+                    let (files, next_file) = (&files, &next_file);
+                    scope.spawn(move || {
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next_file.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = files.get(index) else {
+                                break;
+                            };
+                            results.push((index, worker.convert_batch_file(path, output_dir)));
+                        }
+                        results
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("Conversion worker panicked"))
+                .collect()
+        });
+        results.sort_by_key(|(index, _)| *index);
+
+        self.info(&format!(
+            "⏱️  Converted {} files in {:.2}s using {} threads",
+            files.len(),
+            started.elapsed().as_secs_f64(),
+            thread_count
+        ));
+
+        Ok(files.into_iter().zip(results).map(|(path, (_, result))| (path, result)).collect())
+    }
+
+    /// Converts one file of a batch into `output_dir`
+    fn convert_batch_file(&mut self, mml_path: &Path, output_dir: &str) -> Result<(), ConversionError> {
+        self.info(&format!("📂 Converting {}", mml_path.display()));
+        match (mml_path.to_str(), mp3_output_path(output_dir, mml_path).to_str()) {
+            (Some(mml_path), Some(mp3_path)) => self.convert_mml_to_mp3(mml_path, mp3_path),
+            _ => Err(ConversionError::InvalidArgument(format!("Path is not valid UTF-8: {}", mml_path.display()))),
+        }
+    }
+
+    /// Creates an independent pipeline with the same configuration and its own synthesizer
+    fn worker(&self) -> Result<ConversionPipeline, ConversionError> {
+        let mut midi_converter = MidiConverter::with_sample_rate(self.midi_converter.sample_rate())?;
+        midi_converter.set_gain(self.gain)?;
+        midi_converter.set_polyphony(self.polyphony)?;
+        if let Some(reverb) = self.reverb {
+            midi_converter.set_reverb(reverb.roomsize, reverb.damping, reverb.width, reverb.level)?;
+        }
+        midi_converter.set_release_tail(self.release_tail)?;
+        for soundfont_path in &self.soundfont_paths {
+            midi_converter.load_soundfont(soundfont_path)?;
+        }
+
+        Ok(ConversionPipeline {
+            mml_converter: self.mml_converter.clone(),
+            midi_converter,
+            soundfont_paths: self.soundfont_paths.clone(),
+            gain: self.gain,
+            polyphony: self.polyphony,
+            silence_policy: self.silence_policy,
+            reverb: self.reverb,
+            release_tail: self.release_tail,
+            embed_source: self.embed_source,
+            keep_intermediates: self.keep_intermediates,
+            encoder_config: self.encoder_config.clone(),
+            normalization: self.normalization,
+            waveform_buckets: self.waveform_buckets,
+            deadline: self.deadline,
+            preview_secs: self.preview_secs,
+            temp_dir: self.temp_dir.clone(),
+            log_sink: Arc::clone(&self.log_sink),
+            #[cfg(feature = "ogg")]
+            ogg_quality: self.ogg_quality,
+            #[cfg(feature = "flac")]
+            flac_compression_level: self.flac_compression_level,
+        })
     }
 
     /// Converts MML text directly to MP3
@@ -132,33 +1628,134 @@ impl ConversionPipeline {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` on success, or `Err(String)` with error message.
-    pub fn convert_mml_text_to_mp3(&mut self, mml_text: &str, mp3_output_path: &str) -> Result<(), String> {
+    /// Returns `Ok(())` on success, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_text_to_mp3(&mut self, mml_text: &str, mp3_output_path: &str) -> Result<(), ConversionError> {
         // Validate MML content first
         self.mml_converter.validate_mml(mml_text)?;
 
-        let temp_midi_path = "temp_conversion.mid";
-        let temp_wav_path = "temp_conversion.wav";
+        let temp_files = TempFiles::new(&self.temp_dir)?;
+
+        let result = (|| {
+            // Step 1: MML → MIDI
+            self.info("🎼 Converting MML to MIDI...");
+            let midi_data = self.convert_mml(mml_text)?;
+            self.info("✅ MIDI data generated");
+            self.keep_midi(&temp_files, &midi_data)?;
+
+            self.render_midi_to_mp3(&midi_data, &temp_files, mp3_output_path, Some(mml_text)).map(|_| ())
+        })();
+
+        // Clean up temporary files, even if a stage failed midway
+        self.finish_temp_files(&temp_files);
 
-        // Step 1: MML → MIDI
-        println!("🎼 Converting MML to MIDI...");
-        self.mml_converter.convert_mml_to_midi(mml_text, temp_midi_path)?;
-        println!("✅ MIDI file generated");
+        result
+    }
 
+    /// Runs the MIDI → WAV → MP3 stages on in-memory MIDI data
+    fn render_midi_to_mp3(&mut self, midi_data: &[u8], temp_files: &TempFiles, mp3_output_path: &str, mml_source: Option<&str>) -> Result<ConversionStats, ConversionError> {
         // Step 2: MIDI → WAV
-        println!("🎹 Synthesizing MIDI to WAV...");
-        self.midi_converter.convert_midi_to_wav(temp_midi_path, temp_wav_path)?;
-        println!("✅ WAV file generated");
+        self.info("🎹 Synthesizing MIDI to WAV...");
+        let synthesis_started = Instant::now();
+        let stop = StopConditions {
+            deadline: self.deadline,
+            max_frames: self.preview_secs
+                .map(|secs| (secs * self.midi_converter.sample_rate() as f64).round() as usize),
+            ..StopConditions::default()
+        };
+        let report = self.synthesize(|converter| converter.convert_midi_bytes_to_wav_until(midi_data, &temp_files.wav, stop))?;
+        let midi_to_wav = synthesis_started.elapsed();
+        self.check_silence(&report)?;
+        self.info("✅ WAV file generated");
 
         // Step 3: WAV → MP3
-        println!("🎵 Encoding WAV to MP3...");
-        Mp3Encoder::convert_wav_to_mp3(temp_wav_path, mp3_output_path)?;
-        println!("✅ MP3 encoding completed");
+        self.info("🎵 Encoding WAV to MP3...");
+        let encoding_started = Instant::now();
+        let encoded = self.encode_mp3(&temp_files.wav, mp3_output_path, mml_source)?;
+        let wav_to_mp3 = encoding_started.elapsed();
+        self.info("✅ MP3 encoding completed");
 
-        // Clean up temporary files
-        self.cleanup_temp_files(&[temp_midi_path, temp_wav_path]);
+        Ok(ConversionStats {
+            output_bytes: encoded.output.bytes_written,
+            duration_secs: report.total_samples as f64 / 2.0 / self.midi_converter.sample_rate() as f64,
+            peak_amplitude: report.peak_amplitude,
+            was_clipped: report.is_clipping(),
+            loudness_lufs: encoded.loudness_lufs(),
+            waveform: self.waveform_buckets
+                .map(|buckets| audio_utils::compute_peaks(&encoded.samples, encoded.channels, buckets)),
+            timings: StageTimings { midi_to_wav, wav_to_mp3, ..StageTimings::default() },
+        })
+    }
 
-        Ok(())
+    /// Converts MML text to MP3 data entirely in memory
+    /// 
+    /// Unlike [`ConversionPipeline::convert_mml_text_to_mp3`], no intermediate
+    /// MIDI or WAV files are written, so this works in read-only environments
+    /// and can run alongside other conversions.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_text` - MML code as string
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with the MP3 stream, or `Err(ConversionError)` with error message.
+    pub fn convert_mml_text_to_mp3_bytes(&mut self, mml_text: &str) -> Result<Vec<u8>, ConversionError> {
+        self.mml_converter.validate_mml(mml_text)?;
+
+        let midi_data = self.convert_mml(mml_text)?;
+        self.render_midi_to_mp3_bytes(&midi_data, Some(mml_text))
+    }
+
+    /// Converts in-memory MIDI data to MP3 data without touching the disk
+    /// 
+    /// The instrument settings only affect MML input; MIDI data keeps its own
+    /// program changes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `midi_data` - Standard MIDI file contents
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(Vec<u8>)` with the MP3 stream, or `Err(ConversionError)` with error message.
+    pub fn convert_midi_bytes_to_mp3_bytes(&mut self, midi_data: &[u8]) -> Result<Vec<u8>, ConversionError> {
+        self.render_midi_to_mp3_bytes(midi_data, None)
+    }
+
+    /// Synthesizes and encodes MIDI data in memory, tagging the result
+    fn render_midi_to_mp3_bytes(&mut self, midi_data: &[u8], mml_source: Option<&str>) -> Result<Vec<u8>, ConversionError> {
+        let mut samples = self.synthesize(|converter| converter.render_midi_bytes(midi_data))?;
+        self.check_silence(&SynthReport::from_samples(&samples))?;
+        if let Some(normalization) = self.normalization {
+            normalization.apply(&mut samples, 2, self.midi_converter.sample_rate())?;
+        }
+        let mp3_data = Mp3Encoder::encode_pcm_with_config(&samples, 2, self.midi_converter.sample_rate(), &self.encoder_config)?;
+
+        let Some(tag) = self.id3_tag(mml_source) else {
+            return Ok(mp3_data);
+        };
+
+        let mut tagged = tag.to_bytes();
+        tagged.extend(mp3_data);
+        Ok(tagged)
+    }
+
+    /// Estimates the playback length of an MML file without synthesizing it
+    /// 
+    /// Useful for rejecting overly long songs before spending CPU on rendering.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mml_file_path` - Path to MML file
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(seconds)` on success, or `Err(ConversionError)` with error message.
+    pub fn estimate_mml_duration(&self, mml_file_path: &str) -> Result<f64, ConversionError> {
+        let mml_content = mml_converter::read_mml_file(mml_file_path)
+            .map_err(|e| ConversionError::io("Failed to read MML file", e))?;
+        let midi_data = self.convert_mml(&mml_content)?;
+        Ok(MidiFile::parse(&midi_data)?.duration_secs())
     }
 
     /// Validates an MML file before conversion
@@ -169,18 +1766,136 @@ impl ConversionPipeline {
     /// 
     /// # Returns
     /// 
-    /// Returns `Ok(())` if valid, or `Err(String)` with validation error.
-    pub fn validate_mml_file(&self, mml_file_path: &str) -> Result<(), String> {
+    /// Returns `Ok(())` if valid, or `Err(ConversionError)` with validation error.
+    pub fn validate_mml_file(&self, mml_file_path: &str) -> Result<(), ConversionError> {
         if !Path::new(mml_file_path).exists() {
-            return Err(format!("MML file not found: {}", mml_file_path));
+            return Err(ConversionError::not_found(format!("MML file not found: {}", mml_file_path)));
         }
 
-        let mml_content = fs::read_to_string(mml_file_path)
-            .map_err(|e| format!("Failed to read MML file: {}", e))?;
+        let mml_content = mml_converter::read_mml_file(mml_file_path)
+            .map_err(|e| ConversionError::io("Failed to read MML file", e))?;
 
         self.mml_converter.validate_mml(&mml_content)
     }
 
+    /// Validates every `.mml` file in a directory without rendering anything
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dir` - Directory containing MML files (not searched recursively)
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok` with one entry per MML file, sorted by path, or `Err(ConversionError)`
+    /// if the directory cannot be read. With the `serde` feature enabled the report
+    /// can be serialized, e.g. to JSON for a CI gate.
+    pub fn validate_directory(&self, dir: &str) -> Result<ValidationReport, ConversionError> {
+        let report = mml_files_in(dir)?
+            .into_iter()
+            .map(|path| {
+                let result = match mml_converter::read_mml_file(&path) {
+                    Ok(mml_content) => self.mml_converter.validate_mml_detailed(&mml_content),
+                    Err(e) => Err(vec![ValidationIssue {
+                        line: 0,
+                        column: 0,
+                        message: format!("Failed to read MML file: {}", e),
+                    }]),
+                };
+                (path, result)
+            })
+            .collect();
+
+        Ok(report)
+    }
+
+    /// Encodes the intermediate WAV to MP3, writing the ID3 tag and
+    /// normalizing the peak level if enabled
+    /// 
+    /// Returns the audio as encoded.
+    fn encode_mp3(&self, wav_path: &str, mp3_path: &str, mml_source: Option<&str>) -> Result<EncodedAudio, ConversionError> {
+        let tag = self.id3_tag(mml_source);
+        Mp3Encoder::encode(wav_path, mp3_path, tag.as_ref(), &self.encoder_config, self.normalization)
+    }
+
+    /// Passes a progress message to the log sink
+    fn info(&self, message: &str) {
+        (self.log_sink)(LogLevel::Info, message);
+    }
+
+    /// Passes a warning to the log sink
+    fn warn(&self, message: &str) {
+        (self.log_sink)(LogLevel::Warning, message);
+    }
+
+    /// Converts MML text to MIDI, passing the converter's warnings to the log sink
+    fn convert_mml(&self, mml_text: &str) -> Result<Vec<u8>, ConversionError> {
+        let conversion = self.mml_converter.convert_mml_to_midi_reporting(mml_text)?;
+        for warning in &conversion.warnings {
+            self.warn(warning);
+        }
+        Ok(conversion.midi_data)
+    }
+
+    /// Runs `render` on the MIDI converter, then passes the warnings it
+    /// queued to the log sink, whether or not rendering succeeded
+    fn synthesize<T>(&mut self, render: impl FnOnce(&mut MidiConverter) -> Result<T, ConversionError>) -> Result<T, ConversionError> {
+        let result = render(&mut self.midi_converter);
+        for warning in self.midi_converter.take_warnings() {
+            self.warn(&warning);
+        }
+        result
+    }
+
+    /// Applies the silence policy to a finished render
+    fn check_silence(&self, report: &SynthReport) -> Result<(), ConversionError> {
+        if self.silence_policy == SilencePolicy::Ignore || !report.is_silent() {
+            return Ok(());
+        }
+
+        let message = format!(
+            "Synthesized audio is silent (RMS below {} dBFS); check the instrument number and that the SoundFont has samples for it",
+            midi_converter::SILENCE_RMS_DBFS
+        );
+        if self.silence_policy == SilencePolicy::Error {
+            return Err(ConversionError::SilentOutput(message));
+        }
+        self.warn(&message);
+        Ok(())
+    }
+
+    /// Builds the ID3 tag from the configured metadata and, if embedding is
+    /// enabled, the MML source; returns `None` when there is nothing to write
+    fn id3_tag(&self, mml_source: Option<&str>) -> Option<Id3Tag> {
+        let mut tag = self.encoder_config.to_id3_tag();
+        if let Some(mml_text) = mml_source.filter(|_| self.embed_source) {
+            tag.add_mml_source(mml_text);
+        }
+        (!tag.is_empty()).then_some(tag)
+    }
+
+    /// Writes the MIDI data to its intermediate file if intermediates are kept
+    fn keep_midi(&self, temp_files: &TempFiles, midi_data: &[u8]) -> Result<(), ConversionError> {
+        if self.keep_intermediates {
+            fs::write(&temp_files.midi, midi_data)
+                .map_err(|e| ConversionError::io("Failed to write intermediate MIDI file", e))?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the intermediate files of a run, or lists them if they are kept
+    fn finish_temp_files(&self, temp_files: &TempFiles) {
+        let paths = [temp_files.midi.as_str(), temp_files.wav.as_str()];
+        if !self.keep_intermediates {
+            self.cleanup_temp_files(&paths);
+            return;
+        }
+        for path in paths {
+            if Path::new(path).exists() {
+                self.info(&format!("📌 Kept intermediate file: {}", path));
+            }
+        }
+    }
+
     /// Cleans up temporary files created during conversion
     /// 
     /// # Arguments
@@ -190,9 +1905,9 @@ impl ConversionPipeline {
         for &path in file_paths {
             if Path::new(path).exists() {
                 if let Err(e) = fs::remove_file(path) {
-                    eprintln!("⚠️  Warning: Failed to remove temporary file '{}': {}", path, e);
+                    self.warn(&format!("Failed to remove temporary file '{}': {}", path, e));
                 } else {
-                    println!("🧹 Cleaned up temporary file: {}", path);
+                    self.info(&format!("🧹 Cleaned up temporary file: {}", path));
                 }
             }
         }
@@ -207,17 +1922,17 @@ impl ConversionPipeline {
     /// # Returns
     /// 
     /// Returns file size and basic info about the MML file.
-    pub fn get_conversion_info(&self, mml_file_path: &str) -> Result<String, String> {
+    pub fn get_conversion_info(&self, mml_file_path: &str) -> Result<String, ConversionError> {
         if !Path::new(mml_file_path).exists() {
-            return Err(format!("MML file not found: {}", mml_file_path));
+            return Err(ConversionError::not_found(format!("MML file not found: {}", mml_file_path)));
         }
 
         let metadata = fs::metadata(mml_file_path)
-            .map_err(|e| format!("Failed to read file metadata: {}", e))?;
+            .map_err(|e| ConversionError::io("Failed to read file metadata", e))?;
 
         let file_size = metadata.len();
-        let mml_content = fs::read_to_string(mml_file_path)
-            .map_err(|e| format!("Failed to read MML file: {}", e))?;
+        let mml_content = mml_converter::read_mml_file(mml_file_path)
+            .map_err(|e| ConversionError::io("Failed to read MML file", e))?;
 
         let line_count = mml_content.lines().count();
         let char_count = mml_content.chars().count();
@@ -236,8 +1951,269 @@ impl ConversionPipeline {
     }
 }
 
+/// Builder collecting pipeline settings and applying them in a safe order
+/// 
+/// [`PipelineBuilder::build`] creates the synthesizer at the requested sample
+/// rate, then applies gain and reverb, loads the SoundFonts, and finally sets
+/// the instrument and bitrate. The order the builder methods are called in
+/// does not matter.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use yks_converter_example::pipeline::{PipelineBuilder, ReverbSettings};
+/// 
+/// let mut pipeline = PipelineBuilder::new()
+///     .soundfont("piano.sf2")
+///     .instrument(1)
+///     .bitrate(320)
+///     .sample_rate(48000)
+///     .reverb(ReverbSettings { roomsize: 0.6, damping: 0.3, width: 0.8, level: 0.7 })
+///     .gain(0.8)
+///     .build()?;
+/// pipeline.convert_mml_to_mp3("song.mml", "output.mp3")?;
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PipelineBuilder {
+    soundfonts: Vec<String>,
+    instrument: Option<u8>,
+    bitrate: Option<u32>,
+    sample_rate: u32,
+    reverb: Option<ReverbSettings>,
+    gain: Option<f64>,
+}
+
+impl PipelineBuilder {
+    /// Creates a builder with the default settings of [`ConversionPipeline::new`]
+    pub fn new() -> Self {
+        PipelineBuilder {
+            soundfonts: Vec::new(),
+            instrument: None,
+            bitrate: None,
+            sample_rate: midi_converter::DEFAULT_SAMPLE_RATE,
+            reverb: None,
+            gain: None,
+        }
+    }
+
+    /// Adds a SoundFont; later SoundFonts are layered over earlier ones
+    pub fn soundfont(mut self, soundfont_path: impl Into<String>) -> Self {
+        self.soundfonts.push(soundfont_path.into());
+        self
+    }
+
+    /// Sets the MIDI program used for MML conversion (0-127)
+    pub fn instrument(mut self, program: u8) -> Self {
+        self.instrument = Some(program);
+        self
+    }
+
+    /// Sets the MP3 bitrate in kbps, one of [`crate::mp3_encoder::SUPPORTED_BITRATES`]
+    pub fn bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = Some(bitrate);
+        self
+    }
+
+    /// Sets the synthesis sample rate in Hz (8000-96000)
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Enables reverb with the given parameters
+    pub fn reverb(mut self, reverb: ReverbSettings) -> Self {
+        self.reverb = Some(reverb);
+        self
+    }
+
+    /// Sets the synthesizer gain (0.0-10.0)
+    pub fn gain(mut self, gain: f64) -> Self {
+        self.gain = Some(gain);
+        self
+    }
+
+    /// Creates the pipeline and applies the collected settings
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(ConversionPipeline)` on success, or `Err(ConversionError)` for the
+    /// first setting that is invalid or cannot be applied.
+    pub fn build(self) -> Result<ConversionPipeline, ConversionError> {
+        let mut pipeline = ConversionPipeline::with_sample_rate(self.sample_rate)?;
+
+        if let Some(gain) = self.gain {
+            pipeline.set_gain(gain)?;
+        }
+        if let Some(reverb) = self.reverb {
+            pipeline.set_reverb(reverb)?;
+        }
+        for soundfont_path in &self.soundfonts {
+            pipeline.add_soundfont(soundfont_path)?;
+        }
+        if let Some(program) = self.instrument {
+            pipeline.set_instrument(program)?;
+        }
+        if let Some(bitrate) = self.bitrate {
+            pipeline.set_bitrate(bitrate)?;
+        }
+
+        Ok(pipeline)
+    }
+}
+
+impl Default for PipelineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shareable recipe for creating identically configured pipelines
+/// 
+/// A [`ConversionPipeline`] owns a FluidSynth synthesizer and cannot be
+/// shared between threads. The factory instead stores only the settings of a
+/// [`PipelineBuilder`] (paths and numbers), so it is `Send + Sync` and can be
+/// kept in web server state, with each request creating its own pipeline.
+/// 
+/// Every [`PipelineFactory::create`] loads the SoundFonts again; for many
+/// conversions on one thread, reuse the pipeline instead.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use std::sync::Arc;
+/// use yks_converter_example::pipeline::{PipelineBuilder, PipelineFactory};
+/// 
+/// let factory = Arc::new(PipelineFactory::new(PipelineBuilder::new().soundfont("piano.sf2").bitrate(128)));
+/// 
+/// let handler_factory = Arc::clone(&factory);
+/// std::thread::spawn(move || {
+///     let mut pipeline = handler_factory.create()?;
+///     pipeline.convert_mml_text_to_mp3_bytes("MML@t120l8cdefgab>c;")
+/// })
+/// .join()
+/// .unwrap()?;
+/// # Ok::<(), yks_converter_example::error::ConversionError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PipelineFactory {
+    builder: PipelineBuilder,
+}
+
+impl PipelineFactory {
+    /// Creates a factory producing pipelines configured by `builder`
+    pub fn new(builder: PipelineBuilder) -> Self {
+        PipelineFactory { builder }
+    }
+
+    /// Creates a fresh pipeline with its own synthesizer
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(ConversionPipeline)` on success, or `Err(ConversionError)` for the
+    /// first setting that is invalid or cannot be applied.
+    pub fn create(&self) -> Result<ConversionPipeline, ConversionError> {
+        self.builder.clone().build()
+    }
+}
+
 impl Default for ConversionPipeline {
     fn default() -> Self {
         Self::new().expect("Failed to create ConversionPipeline")
     }
-}
\ No newline at end of file
+}
+
+/// Checks that a preview length is a positive, finite number of seconds
+/// 
+/// # Returns
+/// 
+/// Returns `Ok(())` if valid, or `Err(ConversionError)` describing the problem.
+pub fn validate_preview_secs(seconds: f64) -> Result<(), ConversionError> {
+    if !(seconds.is_finite() && seconds > 0.0) {
+        return Err(ConversionError::InvalidArgument(format!("Preview length must be more than 0 seconds, got {}", seconds)));
+    }
+    Ok(())
+}
+
+/// Joins `repeats` copies of interleaved stereo `samples`, overlapping
+/// consecutive copies by `crossfade_frames` with a linear crossfade
+fn repeat_with_crossfade(samples: &[i16], repeats: usize, crossfade_frames: usize) -> Vec<i16> {
+    let overlap = crossfade_frames.min(samples.len() / 4) * 2;
+    let mut looped = Vec::with_capacity(samples.len() * repeats);
+    looped.extend_from_slice(samples);
+
+    for _ in 1..repeats {
+        let seam = looped.len() - overlap;
+        for (i, (out, &incoming)) in looped[seam..].iter_mut().zip(samples).enumerate() {
+            let t = (i / 2) as f64 / (overlap / 2) as f64;
+            *out = (*out as f64 * (1.0 - t) + incoming as f64 * t).round() as i16;
+        }
+        looped.extend_from_slice(&samples[overlap..]);
+    }
+
+    looped
+}
+
+/// Returns the path of the MP3 in `output_dir` named after `mml_path`
+fn mp3_output_path(output_dir: &str, mml_path: &Path) -> PathBuf {
+    let mut file_name = mml_path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(".mp3");
+    Path::new(output_dir).join(file_name)
+}
+
+/// Lists the `.mml` files directly inside `dir`, sorted by path
+fn mml_files_in(dir: &str) -> Result<Vec<PathBuf>, ConversionError> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| ConversionError::io(format!("Failed to read directory '{}'", dir), e))?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| ConversionError::io("Failed to read directory entry", e))?.path();
+        let is_mml = path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("mml"));
+        if is_mml && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn temp_files_from_concurrent_runs_are_distinct_and_cleaned_up() {
+        let temp_dir = std::env::temp_dir();
+        let runs: Vec<TempFiles> = (0..2)
+            .map(|_| {
+                let temp_dir = temp_dir.clone();
+                thread::spawn(move || {
+                    let temp_files = TempFiles::new(&temp_dir).unwrap();
+                    fs::write(&temp_files.midi, b"MThd").unwrap();
+                    fs::write(&temp_files.wav, b"RIFF").unwrap();
+                    temp_files
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        let paths: Vec<&str> = runs.iter().flat_map(|run| [run.midi.as_str(), run.wav.as_str()]).collect();
+        for (i, path) in paths.iter().enumerate() {
+            assert!(!paths[i + 1..].contains(path), "{} is used twice", path);
+        }
+
+        let mut pipeline = ConversionPipeline::new().unwrap();
+        pipeline.set_log_sink(|_, _| {});
+        for run in &runs {
+            pipeline.finish_temp_files(run);
+        }
+        assert!(paths.iter().all(|path| !Path::new(path).exists()));
+    }
+}
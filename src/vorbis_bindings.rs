@@ -0,0 +1,262 @@
+/*!
+ * Vorbis Encoder Bindings
+ *
+ * Safe Rust bindings for libvorbisenc and libogg, used to write OGG Vorbis files.
+ * Only available with the `ogg` feature.
+ */
+
+use crate::error::ConversionError;
+use crate::ogg_encoder::OggEncoder;
+use libc::{c_char, c_float, c_int, c_long, c_uchar};
+use std::io::Write;
+
+/// Storage for a libvorbis/libogg state struct
+/// 
+/// The state structs (`vorbis_info`, `vorbis_dsp_state`, `vorbis_block`,
+/// `vorbis_comment`, `ogg_stream_state`) are only ever touched through the
+/// library API, so they are kept as aligned storage at least as large as
+/// their C definitions instead of mirroring every field.
+#[repr(C, align(16))]
+pub struct VorbisState {
+    _storage: [u8; 1024],
+}
+
+impl VorbisState {
+    fn boxed() -> Box<Self> {
+        Box::new(VorbisState { _storage: [0; 1024] })
+    }
+}
+
+/// libogg `ogg_page`
+#[repr(C)]
+pub struct ogg_page {
+    pub header: *mut c_uchar,
+    pub header_len: c_long,
+    pub body: *mut c_uchar,
+    pub body_len: c_long,
+}
+
+/// libogg `ogg_packet`
+#[repr(C)]
+pub struct ogg_packet {
+    pub packet: *mut c_uchar,
+    pub bytes: c_long,
+    pub b_o_s: c_long,
+    pub e_o_s: c_long,
+    pub granulepos: i64,
+    pub packetno: i64,
+}
+
+#[link(name = "vorbisenc")]
+unsafe extern "C" {
+    pub fn vorbis_encode_init_vbr(vi: *mut VorbisState, channels: c_long, rate: c_long, base_quality: c_float) -> c_int;
+}
+
+#[link(name = "vorbis")]
+unsafe extern "C" {
+    pub fn vorbis_info_init(vi: *mut VorbisState);
+    pub fn vorbis_info_clear(vi: *mut VorbisState);
+    pub fn vorbis_comment_init(vc: *mut VorbisState);
+    pub fn vorbis_comment_add_tag(vc: *mut VorbisState, tag: *const c_char, contents: *const c_char);
+    pub fn vorbis_comment_clear(vc: *mut VorbisState);
+    pub fn vorbis_analysis_init(vd: *mut VorbisState, vi: *mut VorbisState) -> c_int;
+    pub fn vorbis_block_init(vd: *mut VorbisState, vb: *mut VorbisState) -> c_int;
+    pub fn vorbis_block_clear(vb: *mut VorbisState) -> c_int;
+    pub fn vorbis_dsp_clear(vd: *mut VorbisState);
+    pub fn vorbis_analysis_headerout(
+        vd: *mut VorbisState,
+        vc: *mut VorbisState,
+        op: *mut ogg_packet,
+        op_comm: *mut ogg_packet,
+        op_code: *mut ogg_packet,
+    ) -> c_int;
+    pub fn vorbis_analysis_buffer(vd: *mut VorbisState, vals: c_int) -> *mut *mut c_float;
+    pub fn vorbis_analysis_wrote(vd: *mut VorbisState, vals: c_int) -> c_int;
+    pub fn vorbis_analysis_blockout(vd: *mut VorbisState, vb: *mut VorbisState) -> c_int;
+    pub fn vorbis_analysis(vb: *mut VorbisState, op: *mut ogg_packet) -> c_int;
+    pub fn vorbis_bitrate_addblock(vb: *mut VorbisState) -> c_int;
+    pub fn vorbis_bitrate_flushpacket(vd: *mut VorbisState, op: *mut ogg_packet) -> c_int;
+}
+
+#[link(name = "ogg")]
+unsafe extern "C" {
+    pub fn ogg_stream_init(os: *mut VorbisState, serialno: c_int) -> c_int;
+    pub fn ogg_stream_clear(os: *mut VorbisState) -> c_int;
+    pub fn ogg_stream_packetin(os: *mut VorbisState, op: *mut ogg_packet) -> c_int;
+    pub fn ogg_stream_pageout(os: *mut VorbisState, og: *mut ogg_page) -> c_int;
+    pub fn ogg_stream_flush(os: *mut VorbisState, og: *mut ogg_page) -> c_int;
+}
+
+/// Frames handed to libvorbis per analysis buffer
+const FRAMES_PER_BUFFER: usize = 1024;
+
+/// VBR OGG Vorbis encoder writing to any [`Write`] sink
+/// 
+/// The state structs reference each other by address, so each lives in its
+/// own heap allocation that stays put for the lifetime of the encoder.
+pub struct VorbisEncoder<W: Write> {
+    info: Box<VorbisState>,
+    comment: Box<VorbisState>,
+    dsp: Box<VorbisState>,
+    block: Box<VorbisState>,
+    stream: Box<VorbisState>,
+    channels: usize,
+    output: W,
+}
+
+impl<W: Write> VorbisEncoder<W> {
+    /// Creates a new Vorbis encoder and writes the stream headers to `output`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample_rate` - Audio sample rate (e.g., 44100 for CD quality)
+    /// * `channels` - Number of audio channels (1 for mono, 2 for stereo)
+    /// * `quality` - VBR quality from -0.1 (smallest) to 1.0 (best)
+    /// * `output` - Sink for the OGG pages
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Ok(VorbisEncoder)` on success, or `Err(ConversionError)` with error message.
+    pub fn new(sample_rate: u32, channels: u16, quality: f32, output: W) -> Result<Self, ConversionError> {
+        OggEncoder::validate_quality(quality)?;
+        if channels == 0 {
+            return Err(ConversionError::InvalidArgument("Vorbis encoding needs at least one channel".to_string()));
+        }
+
+        let mut info = VorbisState::boxed();
+        let mut comment = VorbisState::boxed();
+        let mut dsp = VorbisState::boxed();
+        let mut block = VorbisState::boxed();
+        let mut stream = VorbisState::boxed();
+
+        unsafe {
+            vorbis_info_init(&mut *info);
+            if vorbis_encode_init_vbr(&mut *info, channels as c_long, sample_rate as c_long, quality) != 0 {
+                vorbis_info_clear(&mut *info);
+                return Err(ConversionError::Encoding(format!(
+                    "Failed to initialize Vorbis encoder ({} Hz, {} channels)",
+                    sample_rate, channels
+                )));
+            }
+
+            vorbis_comment_init(&mut *comment);
+            vorbis_comment_add_tag(&mut *comment, c"ENCODER".as_ptr(), c"yks-converter-example".as_ptr());
+            vorbis_analysis_init(&mut *dsp, &mut *info);
+            vorbis_block_init(&mut *dsp, &mut *block);
+            ogg_stream_init(&mut *stream, std::process::id() as c_int);
+        }
+
+        let mut encoder = VorbisEncoder { info, comment, dsp, block, stream, channels: channels as usize, output };
+        encoder.write_headers()?;
+        Ok(encoder)
+    }
+
+    /// Encodes interleaved samples in the range -1.0..1.0
+    pub fn encode(&mut self, samples: &[f32]) -> Result<(), ConversionError> {
+        if !samples.len().is_multiple_of(self.channels) {
+            return Err(ConversionError::InvalidArgument(format!(
+                "Sample count {} is not a multiple of the channel count {}",
+                samples.len(),
+                self.channels
+            )));
+        }
+
+        for chunk in samples.chunks(FRAMES_PER_BUFFER * self.channels) {
+            let frames = chunk.len() / self.channels;
+            unsafe {
+                let buffer = vorbis_analysis_buffer(&mut *self.dsp, frames as c_int);
+                for channel in 0..self.channels {
+                    let channel_buffer = std::slice::from_raw_parts_mut(*buffer.add(channel), frames);
+                    for (frame, sample) in channel_buffer.iter_mut().enumerate() {
+                        *sample = chunk[frame * self.channels + channel];
+                    }
+                }
+                vorbis_analysis_wrote(&mut *self.dsp, frames as c_int);
+            }
+            self.write_packets(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Signals the end of the stream and writes the remaining pages
+    /// 
+    /// Pass `&mut sink` to [`VorbisEncoder::new`] to keep using the sink afterwards.
+    pub fn finish(mut self) -> Result<(), ConversionError> {
+        unsafe {
+            vorbis_analysis_wrote(&mut *self.dsp, 0);
+        }
+        self.write_packets(true)?;
+        self.output.flush().map_err(|e| ConversionError::io("Failed to flush OGG data", e))
+    }
+
+    fn write_headers(&mut self) -> Result<(), ConversionError> {
+        let mut header = std::mem::MaybeUninit::<ogg_packet>::uninit();
+        let mut comment = std::mem::MaybeUninit::<ogg_packet>::uninit();
+        let mut code = std::mem::MaybeUninit::<ogg_packet>::uninit();
+
+        unsafe {
+            if vorbis_analysis_headerout(&mut *self.dsp, &mut *self.comment, header.as_mut_ptr(), comment.as_mut_ptr(), code.as_mut_ptr()) != 0 {
+                return Err(ConversionError::Encoding("Failed to create Vorbis headers".to_string()));
+            }
+            ogg_stream_packetin(&mut *self.stream, header.as_mut_ptr());
+            ogg_stream_packetin(&mut *self.stream, comment.as_mut_ptr());
+            ogg_stream_packetin(&mut *self.stream, code.as_mut_ptr());
+        }
+
+        // Audio must start on a fresh page
+        self.write_pages(true)
+    }
+
+    /// Moves finished blocks through the bitrate manager into OGG pages
+    fn write_packets(&mut self, end_of_stream: bool) -> Result<(), ConversionError> {
+        let mut packet = std::mem::MaybeUninit::<ogg_packet>::uninit();
+        unsafe {
+            while vorbis_analysis_blockout(&mut *self.dsp, &mut *self.block) == 1 {
+                vorbis_analysis(&mut *self.block, std::ptr::null_mut());
+                vorbis_bitrate_addblock(&mut *self.block);
+                while vorbis_bitrate_flushpacket(&mut *self.dsp, packet.as_mut_ptr()) == 1 {
+                    ogg_stream_packetin(&mut *self.stream, packet.as_mut_ptr());
+                    self.write_pages(false)?;
+                }
+            }
+        }
+        if end_of_stream { self.write_pages(true) } else { Ok(()) }
+    }
+
+    /// Writes complete pages, or all buffered data when `flush` is set
+    fn write_pages(&mut self, flush: bool) -> Result<(), ConversionError> {
+        let mut raw_page = std::mem::MaybeUninit::<ogg_page>::uninit();
+        loop {
+            let page = unsafe {
+                let ready = if flush {
+                    ogg_stream_flush(&mut *self.stream, raw_page.as_mut_ptr())
+                } else {
+                    ogg_stream_pageout(&mut *self.stream, raw_page.as_mut_ptr())
+                };
+                if ready == 0 {
+                    return Ok(());
+                }
+                raw_page.assume_init_ref()
+            };
+
+            unsafe {
+                self.output.write_all(std::slice::from_raw_parts(page.header, page.header_len as usize))
+                    .and_then(|_| self.output.write_all(std::slice::from_raw_parts(page.body, page.body_len as usize)))
+                    .map_err(|e| ConversionError::io("Failed to write OGG page", e))?;
+            }
+        }
+    }
+}
+
+impl<W: Write> Drop for VorbisEncoder<W> {
+    fn drop(&mut self) {
+        unsafe {
+            ogg_stream_clear(&mut *self.stream);
+            vorbis_block_clear(&mut *self.block);
+            vorbis_dsp_clear(&mut *self.dsp);
+            vorbis_comment_clear(&mut *self.comment);
+            vorbis_info_clear(&mut *self.info);
+        }
+    }
+}
\ No newline at end of file